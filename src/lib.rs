@@ -27,12 +27,18 @@ The following cargo features are currently supported, and provide at least some
 ## Core types
 
 * `nsstring`
+* `nsmutablestring`
+* `nsmutabledata`
 * `nsdata`
 * `nsdictionary`
 * `nsarray`
 * `nsvalue`
 * `nsrange`
 * `nsdate`
+* `nstimezone`
+* `nslocale`
+* `nsdateformatter`
+* `nscalendar`
 * `nsattributedstring`
 
 ## Runtime
@@ -55,6 +61,7 @@ The following cargo features are currently supported, and provide at least some
 * `nsfontdescriptor`
 
 ## Other
+* `bytes` - zero-copy conversions between `NSData` and [bytes](https://crates.io/crates/bytes).  Not included in `all`.
 * `all` - enables all other features
  */
 
@@ -70,6 +77,10 @@ mod types;
 #[cfg(feature="nsurlresponse")]
 mod nsurlresponse;
 mod nsstring;
+#[cfg(feature="nsmutablestring")]
+mod nsmutablestring;
+#[cfg(feature="nsmutabledata")]
+mod nsmutabledata;
 #[cfg(feature="nsthread")]
 mod nsthread;
 #[cfg(feature="nsdictionary")]
@@ -86,23 +97,40 @@ mod nsrange;
 mod nsrunloop;
 #[cfg(feature="nsdate")]
 mod nsdate;
+#[cfg(feature="nstimezone")]
+mod nstimezone;
+#[cfg(feature="nslocale")]
+mod nslocale;
+#[cfg(feature="nsdateformatter")]
+mod nsdateformatter;
+#[cfg(feature="nscalendar")]
+mod nscalendar;
 #[cfg(feature="nsarray")]
 mod nsarray;
 #[cfg(feature="nsenumerator")]
 mod nsenumerator;
 mod nsgeometry;
+mod typeencoding;
 #[cfg(feature = "nsattributedstring")]
 mod nsattributedstring;
+#[cfg(feature = "nsfontdescriptor")]
+mod nsfontdescriptor;
 
 
 pub use objr::foundation::*;
-pub use types::{NSUInteger,NSInteger};
-pub use nsstring::NSStringExtension;
+pub use types::{NSUInteger,NSInteger,NSComparisonResult};
+pub use nsstring::{NSStringExtension,NSStringEncoding,NSStringEncodingConversionOptions,NSStringCompareOptions,NSStringError};
+#[cfg(feature="nsmutablestring")]
+pub use nsmutablestring::NSMutableString;
+#[cfg(feature="nsmutabledata")]
+pub use nsmutabledata::{NSMutableData,NSMutableDataWriter};
+#[cfg(feature="nsrange")]
+pub use nsstring::GetBytesResult;
 
 #[cfg(feature="nsurl")]
 pub use nsurl::NSURL;
 #[cfg(feature="nsdata")]
-pub use nsdata::NSData;
+pub use nsdata::{NSData,NSDataVec,NSDataPathError,NSDataReadingOptions,NSDataWritingOptions,NSDataBase64EncodingOptions,NSDataBase64DecodingOptions,NSDataCompressionAlgorithm};
 #[cfg(feature="nsurlresponse")]
 pub use nsurlresponse::NSURLResponse;
 #[cfg(feature="nsurlrequest")]
@@ -114,24 +142,35 @@ pub use nsurlsession::{NSURLSession,NSURLSessionDownloadTask,NSURLSessionDataTas
 #[cfg(feature="nsthread")]
 pub use nsthread::NSThread;
 #[cfg(feature="nsdictionary")]
-pub use nsdictionary::{NSDictionary,NSDictionaryRaw};
+pub use nsdictionary::{NSDictionary,NSDictionaryRaw,NSMutableDictionary,NSMutableDictionaryRaw,NSDictionaryBuilder,NSDictionaryError,NSDictionaryKey};
 #[cfg(feature="nscopying")]
-pub use nscopying::NSCopying;
+pub use nscopying::{NSCopying,NSMutableCopying};
 #[cfg(feature="nsvalue")]
-pub use nsvalue::NSNumber;
+pub use nsvalue::{NSNumber,NSNumberObjCType,NumberValue,NSValue};
 #[cfg(feature="nsnotification")]
 pub use nsnotification::{NSNotification,NSNotificationName};
 #[cfg(feature="nsrange")]
-pub use nsrange::NSRange;
+pub use nsrange::{NSRange,NSNotFound,NSMaxRange,NSLocationInRange,NSUnionRange,NSIntersectionRange,NSStringFromRange,NSRangeFromString,Utf16IndexMap,Utf16IndexError};
 #[cfg(feature="nsrunloop")]
 pub use nsrunloop::NSRunLoop;
 #[cfg(feature="nsdate")]
-pub use nsdate::NSDate;
+pub use nsdate::{NSDate,NSTimeInterval,NSTimeIntervalSince1970,NSDateRangeError,reference_date,reference_interval_from_system_time,system_time_from_reference_interval};
+#[cfg(feature="nstimezone")]
+pub use nstimezone::NSTimeZone;
+#[cfg(feature="nslocale")]
+pub use nslocale::NSLocale;
+#[cfg(feature="nsdateformatter")]
+pub use nsdateformatter::{NSDateFormatter,NSISO8601DateFormatter,NSISO8601DateFormatOptions,RFC1123_DATE_FORMAT};
+#[cfg(feature="nscalendar")]
+pub use nscalendar::{NSCalendar,NSCalendarIdentifier,NSCalendarUnit,NSCalendarOptions,NSDateComponents,NSDateComponentUndefined};
 #[cfg(feature="nsarray")]
-pub use nsarray::NSArray;
+pub use nsarray::{NSArray,NSMutableArray,NSArrayIndexedIter};
 #[cfg(feature="nsenumerator")]
-pub use nsenumerator::{NSFastEnumeration,FastEnumerator};
+pub use nsenumerator::{NSFastEnumeration,NSFastEnumerationState,FastEnumerator,CheckedFastEnumerator,EnumerationError,NSEnumerator,WithPool,NSFastEnumerationObject,fast_enumeration_object};
 #[cfg(feature="nsattributedstring")]
 pub use nsattributedstring::*;
+#[cfg(feature="nsfontdescriptor")]
+pub use nsfontdescriptor::{NSFontDescriptor,NSFontDescriptorAttributeName,NSFontDescriptorTraitKey,NSFontDescriptorSymbolicTraits,NSFontFamilyClass,UndefinedFamilyClass};
 
-pub use nsgeometry::{NSPoint,NSRect};
\ No newline at end of file
+pub use nsgeometry::*;
+pub use typeencoding::{TypeEncoding,Encoding,EncodingParseError,encode_struct};
\ No newline at end of file
@@ -1,7 +1,10 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::ffi::c_void;
 use std::convert::TryInto;
 use objr::bindings::*;
-use crate::NSUInteger;
-use crate::nsenumerator::{FastEnumerator,NSFastEnumeration,NSFastEnumerationState};
+use crate::{NSComparisonResult, NSInteger, NSRange, NSUInteger, NSNotFound};
+use crate::nsenumerator::{FastEnumerator,CheckedFastEnumerator,NSFastEnumeration,NSFastEnumerationState,NSEnumerator,WithPool};
 
 objc_class! {
     pub struct NSArrayRaw {
@@ -18,6 +21,21 @@ objc_selector_group! {
         @selector("initWithObjects:count:")
         @selector("countByEnumeratingWithState:objects:count:")
         @selector("count")
+        @selector("addObject:")
+        @selector("insertObject:atIndex:")
+        @selector("removeObjectAtIndex:")
+        @selector("replaceObjectAtIndex:withObject:")
+        @selector("removeAllObjects")
+        @selector("sortUsingComparator:")
+        @selector("objectAtIndex:")
+        @selector("firstObject")
+        @selector("lastObject")
+        @selector("subarrayWithRange:")
+        @selector("indexOfObject:")
+        @selector("containsObject:")
+        @selector("objectsAtIndexes:")
+        @selector("addIndex:")
+        @selector("reverseObjectEnumerator")
     }
     impl Selectors for Sel {}
 }
@@ -45,11 +63,125 @@ impl<Element: ObjcInstance + 'static> NSArray<Element> {
     pub fn iter<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> FastEnumerator<&Self> {
         FastEnumerator::new(self, pool)
     }
+    ///Like [Self::iter], but yields an error instead of panicking if the array is mutated during enumeration.
+    pub fn checked_iter<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> CheckedFastEnumerator<&Self> {
+        FastEnumerator::new(self, pool).checked()
+    }
+    ///For use with `for element in array.with_pool(pool)`.
+    pub fn with_pool<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> WithPool<'a, Self> {
+        WithPool::new(self, pool)
+    }
+    ///An index-based iterator.  This is slower than [Self::iter], but it is double-ended.
+    pub fn iter_indexed<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> NSArrayIndexedIter<'a, Element> {
+        NSArrayIndexedIter { array: self, front: 0, back: self.count(pool), pool }
+    }
+    ///Fast-enumerates the array back to front.
+    pub fn reverseObjectEnumerator(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSEnumerator<Element>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::reverseObjectEnumerator(), pool, ());
+            NSEnumerator::assume_nonnil(raw).assume_retained()
+        }
+    }
     pub fn count(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
         unsafe {
             Self::perform_primitive(self.assume_nonmut_perform(), Sel::count(), pool, ())
         }
     }
+
+    ///Borrows the element at `index`, or `None` if out of bounds.
+    ///
+    /// The array retains its elements, so the reference lives as long as the borrow of the array.
+    pub fn get(&self, index: NSUInteger, pool: &ActiveAutoreleasePool) -> Option<&Element> {
+        if index >= self.count(pool) {
+            return None
+        }
+        unsafe {
+            let raw: *const Element = Self::perform_primitive(self.assume_nonmut_perform(), Sel::objectAtIndex_(), pool, (index,));
+            Some(&*raw)
+        }
+    }
+    pub fn first(&self, pool: &ActiveAutoreleasePool) -> Option<&Element> {
+        self.get(0, pool)
+    }
+    pub fn last(&self, pool: &ActiveAutoreleasePool) -> Option<&Element> {
+        self.count(pool).checked_sub(1).and_then(|index| self.get(index, pool))
+    }
+
+    ///Like `objectAtIndex:`, but returns `None` instead of raising for an out-of-bounds index.
+    pub fn objectAtIndex(&self, index: NSUInteger, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Element>> {
+        if index >= self.count(pool) {
+            return None
+        }
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::objectAtIndex_(), pool, (index,));
+            Some(Element::assume_nonnil(raw).assume_retained())
+        }
+    }
+    pub fn firstObject(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Element>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::firstObject(), pool, ());
+            Element::nullable(raw).assume_retained()
+        }
+    }
+    pub fn lastObject(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Element>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::lastObject(), pool, ());
+            Element::nullable(raw).assume_retained()
+        }
+    }
+    ///Returns `None` if `range` extends past the end of the array.
+    pub fn subarrayWithRange(&self, range: NSRange, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Self>> {
+        if range.checked_max()? > self.count(pool) {
+            return None
+        }
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::subarrayWithRange_(), pool, (range,));
+            Some(Self::assume_nonnil(raw).assume_retained())
+        }
+    }
+    ///The lowest index whose element `isEqual:` to `object`.
+    pub fn indexOfObject(&self, object: &Element, pool: &ActiveAutoreleasePool) -> Option<NSUInteger> {
+        let index: NSUInteger = unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::indexOfObject_(), pool, (object.assume_nonmut_perform(),))
+        };
+        if index == NSNotFound { None } else { Some(index) }
+    }
+    pub fn containsObject(&self, object: &Element, pool: &ActiveAutoreleasePool) -> bool {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::containsObject_(), pool, (object.assume_nonmut_perform(),))
+        }
+    }
+    ///Returns the elements at `indexes`, in ascending index order.  Returns `None` if any index is out of bounds.
+    pub fn objectsAtIndexes(&self, indexes: &[NSUInteger], pool: &ActiveAutoreleasePool) -> Option<StrongCell<Self>> {
+        let count = self.count(pool);
+        if indexes.iter().any(|&index| index >= count) {
+            return None
+        }
+        let index_set = NSMutableIndexSet::class().alloc_init(pool);
+        for &index in indexes {
+            unsafe {
+                let _: () = NSMutableIndexSet::perform_primitive(index_set.assume_nonmut_perform(), Sel::addIndex_(), pool, (index,));
+            }
+        }
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::objectsAtIndexes_(), pool, (index_set.assume_nonmut_perform(),));
+            Some(Self::assume_nonnil(raw).assume_retained())
+        }
+    }
+    ///Retains each element, so the result can be used outside the autorelease pool.
+    pub fn to_vec(&self, pool: &ActiveAutoreleasePool) -> Vec<StrongCell<Element>> {
+        let mut vec = Vec::with_capacity(self.count(pool) as usize);
+        for element in self.iter(pool) {
+            vec.push(element.retain());
+        }
+        vec
+    }
+}
+
+objc_class! {
+    pub(crate) struct NSMutableIndexSet {
+        @class(NSMutableIndexSet)
+    }
 }
 
 
@@ -63,6 +195,227 @@ impl<Element: Arguable + 'static> NSFastEnumeration for &NSArray<Element> {
             NSArray::perform_primitive(self.assume_nonmut_perform(), Sel::countByEnumeratingWithState_objects_count(), pool, (state,transmut, count))
         }
     }
+    fn count_hint(self, pool: &ActiveAutoreleasePool) -> Option<NSUInteger> {
+        unsafe {
+            Some(NSArray::perform_primitive(self.assume_nonmut_perform(), Sel::count(), pool, ()))
+        }
+    }
+}
+impl<'e, Element: Arguable + ObjcInstance + 'static> ExactSizeIterator for FastEnumerator<'e, &NSArray<Element>> {}
+
+impl<'a, Element: Arguable + ObjcInstance + 'static> IntoIterator for WithPool<'a, NSArray<Element>> {
+    type Item = &'a Element;
+    type IntoIter = FastEnumerator<'a, &'a NSArray<Element>>;
+    fn into_iter(self) -> Self::IntoIter {
+        FastEnumerator::new(self.value(), self.pool())
+    }
+}
+impl<'a, Element: Arguable + ObjcInstance + 'static> IntoIterator for WithPool<'a, NSMutableArray<Element>> {
+    type Item = &'a Element;
+    type IntoIter = FastEnumerator<'a, &'a NSArray<Element>>;
+    fn into_iter(self) -> Self::IntoIter {
+        FastEnumerator::new(self.value().as_immutable(), self.pool())
+    }
+}
+
+///See [NSArray::iter_indexed].
+pub struct NSArrayIndexedIter<'a, Element> {
+    array: &'a NSArray<Element>,
+    front: NSUInteger,
+    back: NSUInteger,
+    pool: &'a ActiveAutoreleasePool,
+}
+impl<'a, Element: ObjcInstance + 'static> Iterator for NSArrayIndexedIter<'a, Element> {
+    type Item = &'a Element;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None
+        }
+        let element = self.array.get(self.front, self.pool);
+        self.front += 1;
+        element
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.back - self.front) as usize;
+        (remaining, Some(remaining))
+    }
+}
+impl<'a, Element: ObjcInstance + 'static> DoubleEndedIterator for NSArrayIndexedIter<'a, Element> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None
+        }
+        self.back -= 1;
+        self.array.get(self.back, self.pool)
+    }
+}
+impl<'a, Element: ObjcInstance + 'static> ExactSizeIterator for NSArrayIndexedIter<'a, Element> {}
+
+objc_class! {
+    pub struct NSMutableArrayRaw {
+        @class(NSMutableArray)
+    }
+}
+
+objc_class_newtype! {
+    pub struct NSMutableArray<Element>: NSMutableArrayRaw;
+}
+
+blocksr::many_escaping_nonsend!(Comparator(obj1: *const NSObject, obj2: *const NSObject) -> NSInteger);
+unsafe impl Arguable for &Comparator {}
+
+/**
+A Rust closure borrowed for the duration of a synchronous ObjC call, in a form that a heap block can capture.
+
+Blocks must be `'static`, but APIs like `sortUsingComparator:` only call their block before returning.  This erases the
+closure's type and lifetime behind a pointer and a trampoline, so the block captures only `'static` data.
+`Args` are the block's raw arguments; the closure converts them.
+
+A panic inside the closure can't unwind through ObjC, so it aborts the process at the block boundary.
+*/
+pub(crate) struct ScopedClosure<Args, R> {
+    closure: *mut c_void,
+    call: unsafe fn(*mut c_void, Args) -> R,
+    //the closure is FnMut, so calls must not overlap
+    in_call: Cell<bool>,
+}
+impl<Args, R> ScopedClosure<Args, R> {
+    ///# Safety
+    /// Only call this during the [with_scoped_closure] call that created `self`.
+    pub(crate) unsafe fn call(&self, args: Args) -> R {
+        assert!(!self.in_call.replace(true), "scoped closure called reentrantly");
+        let r = (self.call)(self.closure, args);
+        self.in_call.set(false);
+        r
+    }
+}
+unsafe fn call_erased<F: FnMut(Args) -> R, Args, R>(closure: *mut c_void, args: Args) -> R {
+    (*(closure as *mut F))(args)
+}
+
+/**
+Lends `closure` to `f` as a [ScopedClosure].
+
+# Safety
+Nothing `f` creates from the [ScopedClosure] (usually a block) may be called after `f` returns.
+*/
+pub(crate) unsafe fn with_scoped_closure<F: FnMut(Args) -> R, Args, R, T>(mut closure: F, f: impl FnOnce(ScopedClosure<Args, R>) -> T) -> T {
+    let scoped = ScopedClosure {
+        closure: &mut closure as *mut F as *mut c_void,
+        call: call_erased::<F, Args, R>,
+        in_call: Cell::new(false),
+    };
+    f(scoped)
+}
+
+/**
+Wraps a Rust comparator in an `NSComparator` block for the duration of `f`.
+
+# Safety
+`f` must not let the block escape, and the block must only be called with `Element`s.
+*/
+pub(crate) unsafe fn with_comparator<Element, F: FnMut(&Element, &Element) -> Ordering, R>(mut comparator: F, f: impl FnOnce(&Comparator) -> R) -> R {
+    let adapter = move |(obj1, obj2): (*const NSObject, *const NSObject)| {
+        let obj1 = &*(obj1 as *const Element);
+        let obj2 = &*(obj2 as *const Element);
+        NSComparisonResult::from(comparator(obj1, obj2)).raw()
+    };
+    with_scoped_closure(adapter, |scoped| {
+        let block = Comparator::new(move |obj1, obj2| scoped.call((obj1, obj2)));
+        f(&block)
+    })
+}
+
+/**
+`NSMutableArray`.
+
+Foundation raises `NSRangeException` for out-of-bounds indices, so these methods check bounds first and panic instead.
+
+# Mutation during enumeration
+Safe code can't mutate the array while a [FastEnumerator] borrows it.  However, the array may still be mutated
+through another reference (for example, by ObjC code that shares it).  If that happens, the enumerator
+detects it before yielding the next element and panics with `"Mutation!"`, which matches the `NSGenericException`
+Foundation raises in the same situation.  Use [Self::checked_iter] or [FastEnumerator::try_next] to get an
+[crate::EnumerationError] instead, and [FastEnumerator::restart] to start over.
+*/
+#[allow(non_snake_case)]
+impl<Element: ObjcInstance + 'static> NSMutableArray<Element> {
+    pub fn new(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            Self::class().alloc_init(pool).assume_mut()
+        }
+    }
+    pub fn with_slice(objects: &[&Element], pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            //the equivalent of assume_nonmut_perform for this argument
+            let transmut: *mut *mut Element = std::mem::transmute(objects.as_ptr());
+            let raw = Self::perform(alloc, Sel::initWithObjects_count(), pool, (transmut, objects.len() as NSUInteger));
+            Self::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    pub fn as_immutable(&self) -> &NSArray<Element> {
+        unsafe { self.cast() }
+    }
+    pub fn count(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        self.as_immutable().count(pool)
+    }
+    pub fn iter<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> FastEnumerator<&NSArray<Element>> {
+        self.as_immutable().iter(pool)
+    }
+    ///See [NSArray::checked_iter].
+    pub fn checked_iter<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> CheckedFastEnumerator<&NSArray<Element>> {
+        self.as_immutable().checked_iter(pool)
+    }
+    pub fn with_pool<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> WithPool<'a, Self> {
+        WithPool::new(self, pool)
+    }
+    fn check_index(&self, index: NSUInteger, count: NSUInteger) {
+        assert!(index < count, "index {} out of bounds for count {}", index, count);
+    }
+    pub fn addObject(&mut self, object: &Element, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::addObject_(), pool, (object.assume_nonmut_perform(),))
+        }
+    }
+    ///# Panics
+    /// If `index` is greater than the count.
+    pub fn insertObjectAtIndex(&mut self, object: &Element, index: NSUInteger, pool: &ActiveAutoreleasePool) {
+        self.check_index(index, self.count(pool) + 1);
+        unsafe {
+            Self::perform_primitive(self, Sel::insertObject_atIndex(), pool, (object.assume_nonmut_perform(), index))
+        }
+    }
+    ///# Panics
+    /// If `index` is out of bounds.
+    pub fn removeObjectAtIndex(&mut self, index: NSUInteger, pool: &ActiveAutoreleasePool) {
+        self.check_index(index, self.count(pool));
+        unsafe {
+            Self::perform_primitive(self, Sel::removeObjectAtIndex_(), pool, (index,))
+        }
+    }
+    ///# Panics
+    /// If `index` is out of bounds.
+    pub fn replaceObjectAtIndexWithObject(&mut self, index: NSUInteger, object: &Element, pool: &ActiveAutoreleasePool) {
+        self.check_index(index, self.count(pool));
+        unsafe {
+            Self::perform_primitive(self, Sel::replaceObjectAtIndex_withObject(), pool, (index, object.assume_nonmut_perform()))
+        }
+    }
+    pub fn removeAllObjects(&mut self, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::removeAllObjects(), pool, ())
+        }
+    }
+    ///Sorts in place.  `comparator` is called synchronously and not retained.  A panic in `comparator` aborts the process.
+    pub fn sortUsingComparator<F: FnMut(&Element, &Element) -> Ordering>(&mut self, comparator: F, pool: &ActiveAutoreleasePool) {
+        //safety: sortUsingComparator: doesn't keep the block, and the array only contains Element
+        unsafe {
+            with_comparator(comparator, |block| {
+                Self::perform_primitive(self, Sel::sortUsingComparator_(), pool, (block,))
+            })
+        }
+    }
 }
 
 #[test] fn smoke() {
@@ -92,3 +445,133 @@ impl<Element: Arguable + 'static> NSFastEnumeration for &NSArray<Element> {
 
 }
 
+#[test] fn mutable() {
+    autoreleasepool(|pool| {
+        let mut array = NSMutableArray::<NSString>::new(pool);
+        array.addObject(objc_nsstring!("b"), pool);
+        array.addObject(objc_nsstring!("d"), pool);
+        array.insertObjectAtIndex(objc_nsstring!("a"), 0, pool);
+        array.insertObjectAtIndex(objc_nsstring!("c"), 3, pool);
+        assert_eq!(array.count(pool), 4);
+        array.replaceObjectAtIndexWithObject(3, objc_nsstring!("z"), pool);
+        array.removeObjectAtIndex(1, pool);
+        let elements: Vec<String> = array.iter(pool).map(|e| e.to_str(pool).to_owned()).collect();
+        assert_eq!(elements, ["a", "c", "z"]);
+
+        array.sortUsingComparator(|a, b| b.to_str(pool).cmp(a.to_str(pool)), pool);
+        let elements: Vec<String> = array.as_immutable().iter(pool).map(|e| e.to_str(pool).to_owned()).collect();
+        assert_eq!(elements, ["z", "c", "a"]);
+
+        array.removeAllObjects(pool);
+        assert_eq!(array.count(pool), 0);
+    })
+}
+
+#[test] #[should_panic] fn mutable_out_of_bounds() {
+    autoreleasepool(|pool| {
+        let mut array = NSMutableArray::with_slice(&[objc_nsstring!("a")], pool);
+        array.removeObjectAtIndex(1, pool);
+    })
+}
+
+#[test] fn indexed() {
+    autoreleasepool(|pool| {
+        let arr = NSArray::with_slice(&[objc_nsstring!("a"), objc_nsstring!("b"), objc_nsstring!("c")], pool);
+        assert_eq!(arr.get(1, pool).unwrap().to_str(pool), "b");
+        assert!(arr.get(3, pool).is_none());
+        assert_eq!(arr.first(pool).unwrap().to_str(pool), "a");
+        assert_eq!(arr.last(pool).unwrap().to_str(pool), "c");
+        assert_eq!(arr.objectAtIndex(2, pool).unwrap().to_str(pool), "c");
+        assert!(arr.objectAtIndex(3, pool).is_none());
+        assert_eq!(arr.firstObject(pool).unwrap().to_str(pool), "a");
+        assert_eq!(arr.lastObject(pool).unwrap().to_str(pool), "c");
+
+        let sub = arr.subarrayWithRange(NSRange { location: 1, length: 2 }, pool).unwrap();
+        assert_eq!(sub.count(pool), 2);
+        assert!(arr.subarrayWithRange(NSRange { location: 2, length: 2 }, pool).is_none());
+        assert!(arr.subarrayWithRange(NSRange { location: 1, length: NSUInteger::MAX }, pool).is_none());
+
+        //isEqual:, not pointer equality
+        let b = NSString::with_str_copy("b", pool);
+        assert_eq!(arr.indexOfObject(&b, pool), Some(1));
+        assert!(arr.containsObject(&b, pool));
+        assert_eq!(arr.indexOfObject(objc_nsstring!("z"), pool), None);
+        assert!(!arr.containsObject(objc_nsstring!("z"), pool));
+
+        let picked = arr.objectsAtIndexes(&[2, 0], pool).unwrap();
+        assert_eq!(picked.first(pool).unwrap().to_str(pool), "a");
+        assert_eq!(picked.last(pool).unwrap().to_str(pool), "c");
+        assert!(arr.objectsAtIndexes(&[5], pool).is_none());
+
+        let empty = NSArray::<NSString>::with_slice(&[], pool);
+        assert!(empty.first(pool).is_none());
+        assert!(empty.last(pool).is_none());
+        assert!(empty.firstObject(pool).is_none());
+    });
+    //elements outlive the pool
+    let vec = autoreleasepool(|pool| {
+        let arr = NSArray::with_slice(&[objc_nsstring!("a"), objc_nsstring!("b")], pool);
+        arr.to_vec(pool)
+    });
+    assert_eq!(vec.len(), 2);
+}
+
+#[test] fn iterator_ergonomics() {
+    autoreleasepool(|pool| {
+        let strings: Vec<_> = (0..40).map(|i| NSString::with_str_copy(&i.to_string(), pool)).collect();
+        let refs: Vec<&NSString> = strings.iter().map(|s| &**s).collect();
+        let arr = NSArray::with_slice(&refs, pool);
+
+        let mut iter = arr.iter(pool);
+        assert_eq!(iter.len(), 40);
+        iter.next();
+        assert_eq!(iter.size_hint(), (39, Some(39)));
+
+        let mut count = 0;
+        for element in arr.with_pool(pool) {
+            assert_eq!(element.to_str(pool), count.to_string());
+            count += 1;
+        }
+        assert_eq!(count, 40);
+
+        //batches cover every element exactly once, even after a call to next
+        let mut iter = arr.iter(pool);
+        let first = iter.next().unwrap();
+        assert_eq!(first.to_str(pool), "0");
+        let mut batched = Vec::new();
+        while let Some(batch) = iter.next_batch() {
+            assert!(!batch.is_empty());
+            batched.extend(batch.iter().map(|e| e.to_str(pool).to_owned()));
+        }
+        let expected: Vec<String> = (1..40).map(|i| i.to_string()).collect();
+        assert_eq!(batched, expected);
+        assert!(iter.next().is_none());
+
+        let reverse = arr.reverseObjectEnumerator(pool);
+        let reversed: Vec<String> = reverse.with_pool(pool).into_iter().map(|e| e.to_str(pool).to_owned()).collect();
+        let expected: Vec<String> = (0..40).rev().map(|i| i.to_string()).collect();
+        assert_eq!(reversed, expected);
+
+        let indexed: Vec<String> = arr.iter_indexed(pool).rev().map(|e| e.to_str(pool).to_owned()).collect();
+        assert_eq!(indexed, expected);
+        let mut indexed = arr.iter_indexed(pool);
+        assert_eq!(indexed.next().unwrap().to_str(pool), "0");
+        assert_eq!(indexed.next_back().unwrap().to_str(pool), "39");
+        assert_eq!(indexed.len(), 38);
+
+        let empty = NSArray::<NSString>::with_slice(&[], pool);
+        assert!(empty.iter(pool).next_batch().is_none());
+        assert_eq!(empty.iter(pool).len(), 0);
+    })
+}
+
+#[test] fn scoped_closure() {
+    let mut calls = Vec::new();
+    let total = unsafe {
+        with_scoped_closure(|(a, b): (usize, usize)| { calls.push(a); a + b }, |scoped| {
+            scoped.call((1, 2)) + scoped.call((3, 4))
+        })
+    };
+    assert_eq!(total, 10);
+    assert_eq!(calls, [1, 3]);
+}
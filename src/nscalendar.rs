@@ -0,0 +1,344 @@
+use objr::bindings::*;
+use std::ops::BitOr;
+use crate::{NSDate, NSInteger, NSTimeZone, NSUInteger};
+
+objc_class! {
+    pub struct NSCalendar {
+        @class(NSCalendar)
+    }
+}
+objc_class! {
+    pub struct NSDateComponents {
+        @class(NSDateComponents)
+    }
+}
+objc_class_newtype! {
+    pub struct NSCalendarIdentifier: NSString;
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("calendarWithIdentifier:")
+        @selector("currentCalendar")
+        @selector("calendarIdentifier")
+        @selector("timeZone")
+        @selector("setTimeZone:")
+        @selector("dateFromComponents:")
+        @selector("components:fromDate:")
+        @selector("components:fromDate:toDate:options:")
+        @selector("component:fromDate:")
+        @selector("dateByAddingComponents:toDate:options:")
+        @selector("dateByAddingUnit:value:toDate:options:")
+        @selector("startOfDayForDate:")
+        @selector("era")
+        @selector("setEra:")
+        @selector("year")
+        @selector("setYear:")
+        @selector("month")
+        @selector("setMonth:")
+        @selector("day")
+        @selector("setDay:")
+        @selector("hour")
+        @selector("setHour:")
+        @selector("minute")
+        @selector("setMinute:")
+        @selector("second")
+        @selector("setSecond:")
+        @selector("nanosecond")
+        @selector("setNanosecond:")
+        @selector("weekday")
+        @selector("setWeekday:")
+        @selector("weekdayOrdinal")
+        @selector("setWeekdayOrdinal:")
+        @selector("quarter")
+        @selector("setQuarter:")
+        @selector("weekOfMonth")
+        @selector("setWeekOfMonth:")
+        @selector("weekOfYear")
+        @selector("setWeekOfYear:")
+        @selector("yearForWeekOfYear")
+        @selector("setYearForWeekOfYear:")
+    }
+    impl Selectors for Sel {}
+}
+
+extern "C" {
+    static NSCalendarIdentifierGregorian: &'static NSCalendarIdentifier;
+    static NSCalendarIdentifierISO8601: &'static NSCalendarIdentifier;
+}
+impl NSCalendarIdentifier {
+    pub fn gregorian() -> &'static Self {
+        unsafe { &NSCalendarIdentifierGregorian }
+    }
+    pub fn iso8601() -> &'static Self {
+        unsafe { &NSCalendarIdentifierISO8601 }
+    }
+}
+
+///`NSCalendarUnit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSCalendarUnit(pub NSUInteger);
+impl NSCalendarUnit {
+    pub const ERA: Self = Self(1 << 1);
+    pub const YEAR: Self = Self(1 << 2);
+    pub const MONTH: Self = Self(1 << 3);
+    pub const DAY: Self = Self(1 << 4);
+    pub const HOUR: Self = Self(1 << 5);
+    pub const MINUTE: Self = Self(1 << 6);
+    pub const SECOND: Self = Self(1 << 7);
+    pub const WEEKDAY: Self = Self(1 << 9);
+    pub const WEEKDAY_ORDINAL: Self = Self(1 << 10);
+    pub const QUARTER: Self = Self(1 << 11);
+    pub const WEEK_OF_MONTH: Self = Self(1 << 12);
+    pub const WEEK_OF_YEAR: Self = Self(1 << 13);
+    pub const YEAR_FOR_WEEK_OF_YEAR: Self = Self(1 << 14);
+    pub const NANOSECOND: Self = Self(1 << 15);
+    pub const CALENDAR: Self = Self(1 << 20);
+    pub const TIME_ZONE: Self = Self(1 << 21);
+
+    ///Every unit, in order from largest to smallest (with calendar and time zone last).
+    pub const ALL: [Self; 16] = [Self::ERA, Self::YEAR, Self::YEAR_FOR_WEEK_OF_YEAR, Self::QUARTER, Self::MONTH, Self::WEEK_OF_YEAR, Self::WEEK_OF_MONTH,
+        Self::DAY, Self::WEEKDAY, Self::WEEKDAY_ORDINAL, Self::HOUR, Self::MINUTE, Self::SECOND, Self::NANOSECOND, Self::CALENDAR, Self::TIME_ZONE];
+
+    ///Year, month, day, hour, minute and second.
+    pub const DATE_AND_TIME: Self = Self(Self::YEAR.0 | Self::MONTH.0 | Self::DAY.0 | Self::HOUR.0 | Self::MINUTE.0 | Self::SECOND.0);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    ///The individual units that are set, in the order of [Self::ALL].
+    pub fn units(self) -> impl Iterator<Item=NSCalendarUnit> {
+        Self::ALL.into_iter().filter(move |unit| self.contains(*unit))
+    }
+}
+impl BitOr for NSCalendarUnit {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+///`NSCalendarOptions`, for date arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSCalendarOptions(pub NSUInteger);
+impl NSCalendarOptions {
+    ///Adding components doesn't carry into larger units; for example, adding a day to the 31st gives the 1st of the same month.
+    pub const WRAP_COMPONENTS: Self = Self(1 << 0);
+}
+
+///`NSDateComponentUndefined`
+#[allow(non_upper_case_globals)]
+pub const NSDateComponentUndefined: NSInteger = NSInteger::MAX;
+
+///Converts a raw component, where [NSDateComponentUndefined] means "not set", to an `Option`.
+fn component_from_raw(raw: NSInteger) -> Option<NSInteger> {
+    if raw == NSDateComponentUndefined { None } else { Some(raw) }
+}
+///The inverse of [component_from_raw].
+fn component_to_raw(component: Option<NSInteger>) -> NSInteger {
+    component.unwrap_or(NSDateComponentUndefined)
+}
+
+#[allow(non_snake_case)]
+impl NSCalendar {
+    pub fn calendarWithIdentifier(identifier: &NSCalendarIdentifier, pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::calendarWithIdentifier_(), pool, (identifier.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    ///The Gregorian calendar, in the system time zone.
+    pub fn gregorian(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        Self::calendarWithIdentifier(NSCalendarIdentifier::gregorian(), pool)
+    }
+    ///The user's calendar.  This may not be Gregorian.
+    pub fn currentCalendar(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::currentCalendar(), pool, ());
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn calendarIdentifier(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSCalendarIdentifier> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::calendarIdentifier(), pool, ());
+            NSCalendarIdentifier::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn timeZone(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSTimeZone> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::timeZone(), pool, ());
+            NSTimeZone::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn setTimeZone(&mut self, time_zone: &NSTimeZone, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setTimeZone_(), pool, (time_zone.assume_nonmut_perform(),))
+        }
+    }
+    ///`None` if the components don't describe a date in this calendar.
+    pub fn dateFromComponents(&self, components: &NSDateComponents, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSDate>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::dateFromComponents_(), pool, (components.assume_nonmut_perform(),));
+            NSDate::nullable(raw).assume_retained()
+        }
+    }
+    ///Breaks `date` into `units`.  Other components are undefined.
+    pub fn componentsFromDate(&self, units: NSCalendarUnit, date: &NSDate, pool: &ActiveAutoreleasePool) -> StrongMutCell<NSDateComponents> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::components_fromDate(), pool, (units.0, date.assume_nonmut_perform()));
+            NSDateComponents::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    ///The difference from `start` to `end`, in `units`.
+    pub fn componentsFromDateToDate(&self, units: NSCalendarUnit, start: &NSDate, end: &NSDate, options: NSCalendarOptions, pool: &ActiveAutoreleasePool) -> StrongMutCell<NSDateComponents> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::components_fromDate_toDate_options(), pool, (units.0, start.assume_nonmut_perform(), end.assume_nonmut_perform(), options.0));
+            NSDateComponents::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    ///A single component of `date`, such as its day.
+    pub fn componentFromDate(&self, unit: NSCalendarUnit, date: &NSDate, pool: &ActiveAutoreleasePool) -> NSInteger {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::component_fromDate(), pool, (unit.0, date.assume_nonmut_perform()))
+        }
+    }
+    ///`None` if the result is out of range.
+    pub fn dateByAddingComponentsToDate(&self, components: &NSDateComponents, date: &NSDate, options: NSCalendarOptions, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSDate>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::dateByAddingComponents_toDate_options(), pool, (components.assume_nonmut_perform(), date.assume_nonmut_perform(), options.0));
+            NSDate::nullable(raw).assume_retained()
+        }
+    }
+    ///Adds `value` of one `unit`, such as 3 months.  `None` if the result is out of range.
+    pub fn dateByAddingUnitValueToDate(&self, unit: NSCalendarUnit, value: NSInteger, date: &NSDate, options: NSCalendarOptions, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSDate>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::dateByAddingUnit_value_toDate_options(), pool, (unit.0, value, date.assume_nonmut_perform(), options.0));
+            NSDate::nullable(raw).assume_retained()
+        }
+    }
+    ///The first moment of `date`'s day in this calendar's time zone.
+    pub fn startOfDayForDate(&self, date: &NSDate, pool: &ActiveAutoreleasePool) -> StrongCell<NSDate> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::startOfDayForDate_(), pool, (date.assume_nonmut_perform(),));
+            NSDate::assume_nonnil(raw).assume_retained()
+        }
+    }
+}
+
+macro_rules! component {
+    ($get:ident, $set:ident, $set_sel:ident) => {
+        ///`None` if undefined.
+        pub fn $get(&self, pool: &ActiveAutoreleasePool) -> Option<NSInteger> {
+            unsafe {
+                component_from_raw(Self::perform_primitive(self.assume_nonmut_perform(), Sel::$get(), pool, ()))
+            }
+        }
+        ///`None` makes the component undefined.
+        pub fn $set(&mut self, value: Option<NSInteger>, pool: &ActiveAutoreleasePool) {
+            unsafe {
+                Self::perform_primitive(self, Sel::$set_sel(), pool, (component_to_raw(value),))
+            }
+        }
+    }
+}
+
+/**
+`NSDateComponents`.
+
+Components are `Option`s; `None` corresponds to `NSDateComponentUndefined`.
+*/
+#[allow(non_snake_case)]
+impl NSDateComponents {
+    pub fn new(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            Self::class().alloc_init(pool).assume_mut()
+        }
+    }
+    component!(era, setEra, setEra_);
+    component!(year, setYear, setYear_);
+    component!(month, setMonth, setMonth_);
+    component!(day, setDay, setDay_);
+    component!(hour, setHour, setHour_);
+    component!(minute, setMinute, setMinute_);
+    component!(second, setSecond, setSecond_);
+    component!(nanosecond, setNanosecond, setNanosecond_);
+    component!(weekday, setWeekday, setWeekday_);
+    component!(weekdayOrdinal, setWeekdayOrdinal, setWeekdayOrdinal_);
+    component!(quarter, setQuarter, setQuarter_);
+    component!(weekOfMonth, setWeekOfMonth, setWeekOfMonth_);
+    component!(weekOfYear, setWeekOfYear, setWeekOfYear_);
+    component!(yearForWeekOfYear, setYearForWeekOfYear, setYearForWeekOfYear_);
+    pub fn timeZone(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSTimeZone>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::timeZone(), pool, ());
+            NSTimeZone::nullable(raw).assume_retained()
+        }
+    }
+    pub fn setTimeZone(&mut self, time_zone: Option<&NSTimeZone>, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setTimeZone_(), pool, (time_zone.assume_nonmut_perform(),))
+        }
+    }
+}
+
+#[test] fn calendar_units() {
+    assert_eq!(NSCalendarUnit::YEAR.0, 4);
+    assert_eq!(NSCalendarUnit::TIME_ZONE.0, 1 << 21);
+    let units = NSCalendarUnit::DAY | NSCalendarUnit::YEAR | NSCalendarUnit::NANOSECOND;
+    assert!(units.contains(NSCalendarUnit::DAY));
+    assert!(!units.contains(NSCalendarUnit::MONTH));
+    assert_eq!(units.units().collect::<Vec<_>>(), [NSCalendarUnit::YEAR, NSCalendarUnit::DAY, NSCalendarUnit::NANOSECOND]);
+    assert_eq!(NSCalendarUnit::ALL.iter().fold(NSCalendarUnit::default(), |a, b| a | *b).units().count(), 16);
+    assert_eq!(NSCalendarUnit::DATE_AND_TIME.units().count(), 6);
+    assert!(NSCalendarUnit::default().is_empty());
+
+    assert_eq!(component_from_raw(NSDateComponentUndefined), None);
+    assert_eq!(component_from_raw(0), Some(0));
+    assert_eq!(component_to_raw(None), NSInteger::MAX);
+    assert_eq!(component_to_raw(Some(-1)), -1);
+}
+
+#[test] fn calendar_math() {
+    autoreleasepool(|pool| {
+        let mut calendar = NSCalendar::gregorian(pool);
+        calendar.setTimeZone(&NSTimeZone::gmt(pool), pool);
+        assert_eq!(calendar.calendarIdentifier(pool).to_str(pool), "gregorian");
+
+        let mut components = NSDateComponents::new(pool);
+        assert_eq!(components.year(pool), None);
+        components.setYear(Some(2024), pool);
+        components.setMonth(Some(1), pool);
+        components.setDay(Some(31), pool);
+        components.setHour(Some(13), pool);
+        let date = calendar.dateFromComponents(&components, pool).unwrap();
+        assert_eq!(date.timeIntervalSince1970(pool), 1706706000.0);
+
+        let back = calendar.componentsFromDate(NSCalendarUnit::DATE_AND_TIME | NSCalendarUnit::WEEKDAY, &date, pool);
+        assert_eq!((back.year(pool), back.month(pool), back.day(pool), back.hour(pool)), (Some(2024), Some(1), Some(31), Some(13)));
+        assert_eq!(back.weekday(pool), Some(4)); //Wednesday; Sunday is 1
+        assert_eq!(back.quarter(pool), None);
+        assert_eq!(calendar.componentFromDate(NSCalendarUnit::MONTH, &date, pool), 1);
+
+        //one month after January 31 is the end of February
+        let next_month = calendar.dateByAddingUnitValueToDate(NSCalendarUnit::MONTH, 1, &date, NSCalendarOptions::default(), pool).unwrap();
+        assert_eq!(calendar.componentFromDate(NSCalendarUnit::DAY, &next_month, pool), 29);
+
+        let mut offset = NSDateComponents::new(pool);
+        offset.setDay(Some(1), pool);
+        let carried = calendar.dateByAddingComponentsToDate(&offset, &date, NSCalendarOptions::default(), pool).unwrap();
+        assert_eq!(calendar.componentFromDate(NSCalendarUnit::MONTH, &carried, pool), 2);
+        let wrapped = calendar.dateByAddingComponentsToDate(&offset, &date, NSCalendarOptions::WRAP_COMPONENTS, pool).unwrap();
+        assert_eq!(calendar.componentFromDate(NSCalendarUnit::MONTH, &wrapped, pool), 1);
+        assert_eq!(calendar.componentFromDate(NSCalendarUnit::DAY, &wrapped, pool), 1);
+
+        let start = calendar.startOfDayForDate(&date, pool);
+        assert_eq!(date.timeIntervalSinceDate(&start, pool), 13.0 * 3600.0);
+
+        let difference = calendar.componentsFromDateToDate(NSCalendarUnit::DAY | NSCalendarUnit::HOUR, &start, &next_month, NSCalendarOptions::default(), pool);
+        assert_eq!((difference.day(pool), difference.hour(pool)), (Some(29), Some(13)));
+
+        NSCalendar::currentCalendar(pool).calendarIdentifier(pool);
+    })
+}
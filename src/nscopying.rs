@@ -1,9 +1,126 @@
-use objr::bindings::ObjcInstance;
+use objr::bindings::*;
 use crate::NSString;
 
+objc_selector_group! {
+    trait Selectors {
+        @selector("copy")
+        @selector("mutableCopy")
+    }
+    impl Selectors for Sel {}
+}
+
+/**
+`NSCopying`.
+
+Only implement this for immutable classes.  Copying a mutable object produces its immutable counterpart, so `copy()` would
+not return `Self`.
+*/
 pub trait NSCopying: ObjcInstance {
+    ///Calls `copy`, which is `copyWithZone:` with the default zone.  Immutable Foundation classes usually return the receiver retained.
+    fn copy(&self, pool: &ActiveAutoreleasePool) -> StrongCell<Self> where Self: Sized {
+        unsafe {
+            let raw = Self::perform(self.assume_nonmut_perform(), Sel::copy(), pool, ());
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+}
+
+///`NSMutableCopying`, for immutable classes with a mutable counterpart.
+pub trait NSMutableCopying: ObjcInstance {
+    type Mutable: ObjcInstance;
+    ///Calls `mutableCopy`, which is `mutableCopyWithZone:` with the default zone.
+    #[allow(non_snake_case)]
+    fn mutableCopy(&self, pool: &ActiveAutoreleasePool) -> StrongMutCell<Self::Mutable> where Self: Sized {
+        unsafe {
+            let raw = Self::perform(self.assume_nonmut_perform(), Sel::mutableCopy(), pool, ());
+            Self::Mutable::assume_nonnil(raw as *const Self::Mutable).assume_retained().assume_mut()
+        }
+    }
+}
+
+impl NSCopying for NSString {}
+#[cfg(feature="nsmutablestring")]
+impl NSMutableCopying for NSString {
+    type Mutable = crate::NSMutableString;
+}
+
+#[cfg(feature="nsarray")]
+impl<Element: ObjcInstance + 'static> NSCopying for crate::NSArray<Element> {}
+#[cfg(feature="nsarray")]
+impl<Element: ObjcInstance + 'static> NSMutableCopying for crate::NSArray<Element> {
+    type Mutable = crate::NSMutableArray<Element>;
+}
+
+#[cfg(feature="nsdictionary")]
+impl<Key: ObjcInstance + 'static, Value: ObjcInstance + 'static> NSCopying for crate::NSDictionary<Key, Value> {}
+#[cfg(feature="nsdictionary")]
+impl<Key: ObjcInstance + 'static, Value: ObjcInstance + 'static> NSMutableCopying for crate::NSDictionary<Key, Value> {
+    type Mutable = crate::NSMutableDictionary<Key, Value>;
+}
+
+#[cfg(feature="nsattributedstring")]
+impl NSCopying for crate::NSAttributedString {}
+#[cfg(feature="nsattributedstring")]
+impl NSMutableCopying for crate::NSAttributedString {
+    type Mutable = crate::NSMutableAttributedString;
+}
+
+#[cfg(feature="nsurlrequest")]
+impl NSCopying for crate::NSURLRequest {}
+#[cfg(feature="nsurlrequest")]
+impl NSMutableCopying for crate::NSURLRequest {
+    type Mutable = crate::NSMutableURLRequest;
 }
 
-impl NSCopying for NSString {
+#[cfg(feature="nsdata")]
+impl NSCopying for crate::NSData {}
+#[cfg(feature="nsmutabledata")]
+impl NSMutableCopying for crate::NSData {
+    type Mutable = crate::NSMutableData;
+}
+#[cfg(feature="nsvalue")]
+impl NSCopying for crate::NSNumber {}
+#[cfg(feature="nsvalue")]
+impl NSCopying for crate::NSValue {}
+#[cfg(feature="nsurl")]
+impl NSCopying for crate::NSURL {}
+#[cfg(feature="nsdate")]
+impl NSCopying for crate::NSDate {}
+#[cfg(feature="nstimezone")]
+impl NSCopying for crate::NSTimeZone {}
+#[cfg(feature="nslocale")]
+impl NSCopying for crate::NSLocale {}
+#[cfg(feature="nscalendar")]
+impl NSCopying for crate::NSCalendar {}
+#[cfg(feature="nscalendar")]
+impl NSCopying for crate::NSDateComponents {}
+#[cfg(feature="nsurlresponse")]
+impl NSCopying for crate::NSURLResponse {}
+#[cfg(feature="nsnotification")]
+impl NSCopying for crate::NSNotification {}
+
+#[test] fn copy_string() {
+    autoreleasepool(|pool| {
+        let string = NSString::with_str_copy("hello", pool);
+        let copy = string.copy(pool);
+        assert_eq!(copy.to_str(pool), "hello");
+    })
+}
 
-}
\ No newline at end of file
+#[cfg(all(feature="nsarray", feature="nsmutablestring"))]
+#[test] fn mutable_copy() {
+    use crate::NSStringExtension;
+    autoreleasepool(|pool| {
+        let mut string = objc_nsstring!("hello").mutableCopy(pool);
+        string.appendString(objc_nsstring!(" world"), pool);
+        assert_eq!(string.as_immutable().to_str(pool), "hello world");
+        assert_eq!(string.as_immutable().length(pool), 11);
+
+        let array = crate::NSArray::with_slice(&[objc_nsstring!("a")], pool);
+        let mut mutable = array.mutableCopy(pool);
+        mutable.addObject(objc_nsstring!("b"), pool);
+        assert_eq!(mutable.count(pool), 2);
+        assert_eq!(array.count(pool), 1);
+        assert_eq!(array.copy(pool).count(pool), 1);
+    })
+}
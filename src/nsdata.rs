@@ -1,5 +1,9 @@
 use objr::bindings::*;
-use crate::NSUInteger;
+use crate::{NSInteger, NSStringEncoding, NSStringError, NSStringExtension, NSUInteger};
+use std::fmt::{Display, Formatter};
+use std::ops::BitOr;
+use std::path::Path;
+use std::sync::Arc;
 objc_class! {
     pub struct NSData {
         @class(NSData)
@@ -14,12 +18,188 @@ objc_selector_group! {
         @selector("length")
         @selector("bytes")
         @selector("writeToFile:atomically:")
+        @selector("dataWithContentsOfFile:options:error:")
+        @selector("dataWithContentsOfURL:options:error:")
+        @selector("writeToFile:options:error:")
+        @selector("writeToURL:options:error:")
+        @selector("initWithBase64EncodedString:options:")
+        @selector("base64EncodedStringWithOptions:")
+        @selector("compressedDataUsingAlgorithm:error:")
+        @selector("decompressedDataUsingAlgorithm:error:")
     }
     impl NSDataSelectors for Sel {}
 }
 
+///`NSDataReadingOptions`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSDataReadingOptions(pub NSUInteger);
+impl NSDataReadingOptions {
+    ///Map the file into memory if Foundation considers it safe (for example, not on a network volume).
+    pub const MAPPED_IF_SAFE: Self = Self(1 << 0);
+    ///Hint that the file should not be kept in the file-system cache.
+    pub const UNCACHED: Self = Self(1 << 1);
+    ///Map the file into memory if possible.
+    pub const MAPPED_ALWAYS: Self = Self(1 << 3);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+impl BitOr for NSDataReadingOptions {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/**
+`NSDataWritingOptions`
+
+The file protection classes are a 4-bit field rather than flags; combine at most one of them with the other options.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSDataWritingOptions(pub NSUInteger);
+impl NSDataWritingOptions {
+    ///Write to a temporary file, then rename it into place.
+    pub const ATOMIC: Self = Self(1 << 0);
+    ///Fail if the file already exists.  Can't be combined with [Self::ATOMIC].
+    pub const WITHOUT_OVERWRITING: Self = Self(1 << 1);
+    pub const FILE_PROTECTION_NONE: Self = Self(0x10000000);
+    pub const FILE_PROTECTION_COMPLETE: Self = Self(0x20000000);
+    pub const FILE_PROTECTION_COMPLETE_UNLESS_OPEN: Self = Self(0x30000000);
+    pub const FILE_PROTECTION_COMPLETE_UNTIL_FIRST_USER_AUTHENTICATION: Self = Self(0x40000000);
+    pub const FILE_PROTECTION_MASK: Self = Self(0xf0000000);
+
+    ///Checks the flag bits.  For the protection class, compare [Self::file_protection] instead.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    ///The file protection class, or the default value if none was set.
+    pub fn file_protection(self) -> Self {
+        Self(self.0 & Self::FILE_PROTECTION_MASK.0)
+    }
+}
+impl BitOr for NSDataWritingOptions {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+///`NSDataBase64EncodingOptions`.  Without a line length, the output is a single line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSDataBase64EncodingOptions(pub NSUInteger);
+impl NSDataBase64EncodingOptions {
+    pub const LINE_LENGTH_64: Self = Self(1 << 0);
+    pub const LINE_LENGTH_76: Self = Self(1 << 1);
+    ///With a line length, end lines with CR.  If neither line ending is given, lines end with CRLF.
+    pub const END_LINE_WITH_CARRIAGE_RETURN: Self = Self(1 << 4);
+    ///With a line length, end lines with LF.  If neither line ending is given, lines end with CRLF.
+    pub const END_LINE_WITH_LINE_FEED: Self = Self(1 << 5);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+impl BitOr for NSDataBase64EncodingOptions {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+///`NSDataBase64DecodingOptions`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSDataBase64DecodingOptions(pub NSUInteger);
+impl NSDataBase64DecodingOptions {
+    ///Skip characters outside the Base64 alphabet, such as line endings.  Without this, they are an error.
+    pub const IGNORE_UNKNOWN_CHARACTERS: Self = Self(1 << 0);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+///`NSDataCompressionAlgorithm`
+#[repr(i64)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NSDataCompressionAlgorithm {
+    ///Apple's LZFSE, a good default on Apple platforms.
+    LZFSE = 0,
+    ///Fastest, with the lowest compression ratio.
+    LZ4 = 1,
+    ///Slowest, with the highest compression ratio.
+    LZMA = 2,
+    ///Raw DEFLATE (RFC 1951), without the zlib header or checksum.
+    Zlib = 3,
+}
+
+///Errors from [NSData::with_contents_of_path] and [NSData::write_to_path].
+#[derive(Debug)]
+pub enum NSDataPathError {
+    ///The path can't be converted to an `NSString`, usually because it isn't UTF-8.
+    InvalidPath(NSStringError),
+    ///Foundation failed to read or write the file.
+    Foundation(StrongMutCell<NSError>),
+}
+impl Display for NSDataPathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NSDataPathError::InvalidPath(e) => write!(f, "Invalid path: {}", e),
+            NSDataPathError::Foundation(e) => write!(f, "{}", e),
+        }
+    }
+}
+impl std::error::Error for NSDataPathError {}
+
+///Copies `path` into an `NSString`, so nothing Foundation keeps (like an `NSError`) borrows it.
+fn path_string(path: &Path, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSString>, NSDataPathError> {
+    use std::os::unix::ffi::OsStrExt;
+    NSString::from_bytes(path.as_os_str().as_bytes(), NSStringEncoding::UTF8, pool).map_err(NSDataPathError::InvalidPath)
+}
+
 blocksr::once_escaping!(Deallocator(ptr: *const std::ffi::c_void, length: NSUInteger) -> ());
 unsafe impl Arguable for &Deallocator {}
+
+/**
+An [NSData] that owns a `Vec` without copying it.  Create with [NSData::from_vec].
+
+Derefs to [NSData], and [NSDataVec::into_vec] gets the `Vec` back.
+
+There is no `into_vec` on [NSData] itself.  A plain `StrongCell<NSData>` can't prove that nothing else uses its
+buffer, so only this handle can take the `Vec` back without copying.  NSData from [NSData::from_arc],
+[NSData::from_boxed_bytes] or elsewhere can only be copied out, with `as_slice(pool).to_vec()`.
+*/
+pub struct NSDataVec {
+    data: StrongCell<NSData>,
+    //the deallocator holds another reference until Foundation is done with the buffer
+    vec: Arc<Vec<u8>>,
+}
+impl NSDataVec {
+    /**
+    Recovers the `Vec`.
+
+    If nothing else holds a reference to the NSData, this returns the original `Vec` with its capacity intact.
+    Otherwise (for example, if ObjC retained the NSData), the NSData keeps the buffer and this returns a copy.
+    */
+    pub fn into_vec(self) -> Vec<u8> {
+        let NSDataVec { data, vec } = self;
+        //if this was the last reference, deallocation releases the deallocator's Arc
+        std::mem::drop(data);
+        Arc::unwrap_or_clone(vec)
+    }
+    ///Gives up the ability to recover the `Vec`.  The NSData still owns it.
+    pub fn into_data(self) -> StrongCell<NSData> {
+        self.data
+    }
+}
+impl std::ops::Deref for NSDataVec {
+    type Target = NSData;
+    fn deref(&self) -> &NSData {
+        &self.data
+    }
+}
+
 #[allow(non_snake_case)]
 impl NSData {
     ///This creates a NSData by borrowing the data argument.
@@ -34,21 +214,114 @@ impl NSData {
         Self::assume_nonnil(init).assume_retained_limited()
     }
 
+    ///Creates an NSData that owns `data` without copying it.  The box is dropped when Foundation deallocates the NSData.
     pub fn from_boxed_bytes(data: Box<[u8]>, pool: &ActiveAutoreleasePool) -> StrongCell<NSData> {
+        if data.is_empty() {
+            return Self::empty(pool);
+        }
+        let len = data.len() as NSUInteger;
+        let ptr = Box::into_raw(data) as *const u8;
+        unsafe {
+            //the block captures nothing; Foundation passes back the pointer and length to rebuild the box
+            let block = Deallocator::new(|ptr, length| {
+                std::mem::drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr as *mut u8, length as usize)));
+            });
+            Self::with_deallocator(ptr, len, &block, pool)
+        }
+    }
+
+    /**
+    Creates an NSData that owns `data` without copying it.
+
+    The `Vec` is dropped when Foundation deallocates the NSData, or can be recovered with its capacity intact by [NSDataVec::into_vec].
+    Only the returned handle can recover it; see [NSDataVec].
+    */
+    pub fn from_vec(data: Vec<u8>, pool: &ActiveAutoreleasePool) -> NSDataVec {
+        let vec = Arc::new(data);
+        if vec.is_empty() {
+            return NSDataVec { data: Self::empty(pool), vec };
+        }
+        let ptr = vec.as_ptr();
+        let len = vec.len() as NSUInteger;
+        let owner = vec.clone();
+        unsafe {
+            let block = Deallocator::new(move |_ptr, _length| {
+                std::mem::drop(owner);
+            });
+            NSDataVec { data: Self::with_deallocator(ptr, len, &block, pool), vec }
+        }
+    }
+
+    ///Creates an NSData that shares `data` without copying it.  The NSData holds a reference until it is deallocated.
+    pub fn from_arc(data: Arc<[u8]>, pool: &ActiveAutoreleasePool) -> StrongCell<NSData> {
+        if data.is_empty() {
+            return Self::empty(pool);
+        }
+        let ptr = data.as_ptr();
+        let len = data.len() as NSUInteger;
+        unsafe {
+            let block = Deallocator::new(move |_ptr, _length| {
+                std::mem::drop(data);
+            });
+            Self::with_deallocator(ptr, len, &block, pool)
+        }
+    }
+
+    ///Creates an NSData that shares `data` without copying it.
+    #[cfg(feature="bytes")]
+    pub fn from_bytes(data: bytes::Bytes, pool: &ActiveAutoreleasePool) -> StrongCell<NSData> {
+        if data.is_empty() {
+            return Self::empty(pool);
+        }
+        let ptr = data.as_ptr();
+        let len = data.len() as NSUInteger;
         unsafe {
-            let uninitialized = NSData::class().alloc(pool);
-            let ptr = data.as_ptr();
-            let len = data.len() as NSUInteger;
-            //todo: I think this could be rewritten as a global block by reconstructing the box via length and pointer
-            let block = Deallocator::new(move |_ptr,_length| {
+            let block = Deallocator::new(move |_ptr, _length| {
                 std::mem::drop(data);
             });
-            let r = Self::perform(uninitialized, Sel::initWithBytesNoCopy_length_deallocator(), pool, (ptr.assume_nonmut_perform(), len, &block));
-            NSData::assume_nonnil(r).assume_retained()
+            Self::with_deallocator(ptr, len, &block, pool)
         }
+    }
+
+    /**
+    Converts to [bytes::Bytes] without copying the contents.
 
+    This retains an immutable copy of the receiver (which for an immutable NSData is the receiver itself),
+    so later changes to an `NSMutableData` are not visible in the result.
+    */
+    #[cfg(feature="bytes")]
+    pub fn to_bytes(&self, pool: &ActiveAutoreleasePool) -> bytes::Bytes {
+        use crate::NSCopying;
+        struct Owner {
+            //keeps `ptr` alive
+            _data: StrongCell<NSData>,
+            ptr: *const u8,
+            len: usize,
+        }
+        //NSData is Send + Sync, and the bytes of an immutable NSData don't change
+        unsafe impl Send for Owner {}
+        unsafe impl Sync for Owner {}
+        impl AsRef<[u8]> for Owner {
+            fn as_ref(&self) -> &[u8] {
+                unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+            }
+        }
+        let data = self.copy(pool);
+        let slice = data.as_slice(pool);
+        let (ptr, len) = (slice.as_ptr(), slice.len());
+        bytes::Bytes::from_owner(Owner { _data: data, ptr, len })
     }
 
+    fn empty(pool: &ActiveAutoreleasePool) -> StrongCell<NSData> {
+        unsafe {
+            NSData::class().alloc_init(pool)
+        }
+    }
+    unsafe fn with_deallocator(ptr: *const u8, len: NSUInteger, deallocator: &Deallocator, pool: &ActiveAutoreleasePool) -> StrongCell<NSData> {
+        let uninitialized = NSData::class().alloc(pool);
+        let r = Self::perform(uninitialized, Sel::initWithBytesNoCopy_length_deallocator(), pool, (ptr.assume_nonmut_perform(), len, deallocator));
+        NSData::assume_nonnil(r).assume_retained()
+    }
     pub fn length(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
         unsafe {
             Self::perform_primitive(self.assume_nonmut_perform(), Sel::length(), &pool, ())
@@ -61,12 +334,87 @@ impl NSData {
             std::slice::from_raw_parts(ptr,length as usize)
         }
     }
+    ///Decodes Base64, or returns `None` if `string` is not valid Base64.
+    pub fn withBase64EncodedString(string: &NSString, options: NSDataBase64DecodingOptions, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSData>> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithBase64EncodedString_options(), pool, (string.assume_nonmut_perform(), options.0));
+            Self::nullable(raw).map(|data| data.assume_retained())
+        }
+    }
+    pub fn base64EncodedStringWithOptions(&self, options: NSDataBase64EncodingOptions, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::base64EncodedStringWithOptions_(), pool, (options.0,));
+            NSString::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///Requires macOS 10.15.
+    pub fn compressedDataUsingAlgorithm(&self, algorithm: NSDataCompressionAlgorithm, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSData>, StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::compressedDataUsingAlgorithm_error(), pool, (algorithm as NSInteger, &mut error as *mut *const NSError));
+            Self::nullable(raw).map(|data| data.assume_retained()).ok_or_else(|| NSError::assume_nonnil(error).retain().assume_mut())
+        }
+    }
+    ///Fails if the receiver was not compressed with `algorithm`.  Requires macOS 10.15.
+    pub fn decompressedDataUsingAlgorithm(&self, algorithm: NSDataCompressionAlgorithm, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSData>, StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::decompressedDataUsingAlgorithm_error(), pool, (algorithm as NSInteger, &mut error as *mut *const NSError));
+            Self::nullable(raw).map(|data| data.assume_retained()).ok_or_else(|| NSError::assume_nonnil(error).retain().assume_mut())
+        }
+    }
+    ///Returns `false` on failure.  To find out why, use [Self::writeToFile].
     pub fn writeToFileAtomically(&self, path: &NSString, atomically: bool, pool: &ActiveAutoreleasePool) -> bool {
         unsafe {
             let r: bool = Self::perform_primitive(self.assume_nonmut_perform(), Sel::writeToFile_atomically(), &pool, (path.assume_nonmut_perform(), atomically));
             r
         }
     }
+
+    ///Reads the file at `path`.
+    pub fn dataWithContentsOfFile(path: &NSString, options: NSDataReadingOptions, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSData>, StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::dataWithContentsOfFile_options_error(), pool, (path.assume_nonmut_perform(), options.0, &mut error as *mut *const NSError));
+            Self::nullable(raw).map(|data| data.assume_retained()).ok_or_else(|| NSError::assume_nonnil(error).retain().assume_mut())
+        }
+    }
+    ///Reads the contents of `url`.  For remote URLs this blocks; prefer `NSURLSession`.
+    #[cfg(feature="nsurl")]
+    pub fn dataWithContentsOfURL(url: &crate::NSURL, options: NSDataReadingOptions, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSData>, StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::dataWithContentsOfURL_options_error(), pool, (url.assume_nonmut_perform(), options.0, &mut error as *mut *const NSError));
+            Self::nullable(raw).map(|data| data.assume_retained()).ok_or_else(|| NSError::assume_nonnil(error).retain().assume_mut())
+        }
+    }
+    ///Reads the file at `path`, which must be UTF-8.
+    pub fn with_contents_of_path(path: &Path, options: NSDataReadingOptions, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSData>, NSDataPathError> {
+        let path = path_string(path, pool)?;
+        Self::dataWithContentsOfFile(&path, options, pool).map_err(NSDataPathError::Foundation)
+    }
+    pub fn writeToFile(&self, path: &NSString, options: NSDataWritingOptions, pool: &ActiveAutoreleasePool) -> Result<(), StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let success: bool = Self::perform_primitive(self.assume_nonmut_perform(), Sel::writeToFile_options_error(), pool, (path.assume_nonmut_perform(), options.0, &mut error as *mut *const NSError));
+            if success { Ok(()) } else { Err(NSError::assume_nonnil(error).retain().assume_mut()) }
+        }
+    }
+    ///Writes to a file URL.
+    #[cfg(feature="nsurl")]
+    pub fn writeToURL(&self, url: &crate::NSURL, options: NSDataWritingOptions, pool: &ActiveAutoreleasePool) -> Result<(), StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let success: bool = Self::perform_primitive(self.assume_nonmut_perform(), Sel::writeToURL_options_error(), pool, (url.assume_nonmut_perform(), options.0, &mut error as *mut *const NSError));
+            if success { Ok(()) } else { Err(NSError::assume_nonnil(error).retain().assume_mut()) }
+        }
+    }
+    ///Writes to the file at `path`, which must be UTF-8.
+    pub fn write_to_path(&self, path: &Path, options: NSDataWritingOptions, pool: &ActiveAutoreleasePool) -> Result<(), NSDataPathError> {
+        let path = path_string(path, pool)?;
+        self.writeToFile(&path, options, pool).map_err(NSDataPathError::Foundation)
+    }
 }
 
 #[test] fn with_borrowed_data() {
@@ -95,4 +443,141 @@ impl NSData {
     let data = NSData::from_boxed_bytes(data, &pool);
     let path = NSString::with_str_copy("test.txt", &pool);
     data.writeToFileAtomically(&path, true, &pool);
-}
\ No newline at end of file
+}
+
+#[test] fn vec_round_trip() {
+    autoreleasepool(|pool| {
+        //large enough that Foundation won't copy it inline
+        let mut vec = vec![7u8; 1 << 20];
+        vec.reserve(10);
+        let (ptr, capacity) = (vec.as_ptr(), vec.capacity());
+        let data = NSData::from_vec(vec, pool);
+        assert_eq!(data.as_slice(pool).as_ptr(), ptr);
+        assert_eq!(data.length(pool), 1 << 20);
+        let vec = data.into_vec();
+        assert_eq!((vec.as_ptr(), vec.capacity(), vec.len()), (ptr, capacity, 1 << 20));
+
+        //a second reference forces a copy
+        let data = NSData::from_vec(vec, pool);
+        let other = data.retain();
+        let copied = data.into_vec();
+        assert_ne!(copied.as_ptr(), ptr);
+        assert_eq!(copied, other.as_slice(pool));
+
+        let empty = NSData::from_vec(Vec::with_capacity(8), pool);
+        assert_eq!(empty.length(pool), 0);
+        assert!(empty.into_vec().capacity() >= 8);
+        let boxed = vec![9u8; 1 << 20].into_boxed_slice();
+        let boxed_ptr = boxed.as_ptr();
+        let boxed = NSData::from_boxed_bytes(boxed, pool);
+        assert_eq!(boxed.as_slice(pool).as_ptr(), boxed_ptr);
+        assert_eq!(boxed.length(pool), 1 << 20);
+        assert_eq!(NSData::from_boxed_bytes(Box::new([]), pool).length(pool), 0);
+    })
+}
+
+#[test] fn vec_shared_buffer() {
+    autoreleasepool(|pool| {
+        let data = NSData::from_vec(vec![5u8; 4096], pool);
+        let ptr = data.as_slice(pool).as_ptr();
+        //ObjC keeps the NSData, and another object views the same buffer
+        let kept = data.retain();
+        let view = unsafe { NSData::from_borrowed_bytes(kept.as_slice(pool), pool) };
+        assert_eq!(view.as_slice(pool).as_ptr(), ptr);
+
+        let vec = data.into_vec();
+        assert_ne!(vec.as_ptr(), ptr);
+        assert_eq!(view.as_slice(pool), &vec[..]);
+        assert_eq!(kept.as_slice(pool), &vec[..]);
+    })
+}
+
+#[test] fn with_arc() {
+    let shared: Arc<[u8]> = Arc::from(&b"shared bytes"[..]);
+    autoreleasepool(|pool| {
+        let data = NSData::from_arc(shared.clone(), pool);
+        assert_eq!(data.as_slice(pool).as_ptr(), shared.as_ptr());
+        assert_eq!(data.as_slice(pool), b"shared bytes");
+    });
+    assert_eq!(Arc::strong_count(&shared), 1);
+}
+
+#[cfg(feature="bytes")]
+#[test] fn with_bytes() {
+    autoreleasepool(|pool| {
+        let original = bytes::Bytes::from(vec![3u8; 4096]);
+        let data = NSData::from_bytes(original.clone(), pool);
+        assert_eq!(data.as_slice(pool).as_ptr(), original.as_ptr());
+        let back = data.to_bytes(pool);
+        assert_eq!(back.as_ptr(), original.as_ptr());
+        assert_eq!(back, original);
+    })
+}
+
+#[test] fn file_io() {
+    let path = std::env::temp_dir().join(format!("foundationr-nsdata-{}.bin", std::process::id()));
+    autoreleasepool(|pool| {
+        let data = NSData::from_vec(b"file contents".to_vec(), pool);
+        data.write_to_path(&path, NSDataWritingOptions::ATOMIC, pool).unwrap();
+        assert!(data.write_to_path(&path, NSDataWritingOptions::WITHOUT_OVERWRITING, pool).is_err());
+
+        let read = NSData::with_contents_of_path(&path, NSDataReadingOptions::MAPPED_IF_SAFE | NSDataReadingOptions::UNCACHED, pool).unwrap();
+        assert_eq!(read.as_slice(pool), b"file contents");
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(NSData::with_contents_of_path(&path, NSDataReadingOptions::default(), pool), Err(NSDataPathError::Foundation(_))));
+
+        use std::os::unix::ffi::OsStrExt;
+        let invalid = std::env::temp_dir().join(std::ffi::OsStr::from_bytes(b"foundationr-\xff.bin"));
+        assert!(matches!(NSData::with_contents_of_path(&invalid, NSDataReadingOptions::default(), pool), Err(NSDataPathError::InvalidPath(_))));
+        assert!(matches!(data.write_to_path(&invalid, NSDataWritingOptions::ATOMIC, pool), Err(NSDataPathError::InvalidPath(_))));
+
+        let options = NSDataWritingOptions::ATOMIC | NSDataWritingOptions::FILE_PROTECTION_COMPLETE;
+        assert!(options.contains(NSDataWritingOptions::ATOMIC));
+        assert_eq!(options.file_protection(), NSDataWritingOptions::FILE_PROTECTION_COMPLETE);
+        assert_eq!(NSDataWritingOptions::ATOMIC.file_protection(), NSDataWritingOptions::default());
+    })
+}
+
+#[cfg(feature="nsurl")]
+#[test] fn url_io() {
+    let path = std::env::temp_dir().join(format!("foundationr-nsdata-url-{}.bin", std::process::id()));
+    autoreleasepool(|pool| {
+        let url = crate::NSURL::initFileURLWithPath(&NSString::from_borrowed_os_str(path.as_os_str(), pool), pool);
+        let data = NSData::from_vec(vec![1, 2, 3], pool);
+        data.writeToURL(&url, NSDataWritingOptions::WITHOUT_OVERWRITING, pool).unwrap();
+        let read = NSData::dataWithContentsOfURL(&url, NSDataReadingOptions::default(), pool).unwrap();
+        assert_eq!(read.as_slice(pool), &[1, 2, 3]);
+        std::fs::remove_file(&path).unwrap();
+    })
+}
+
+#[test] fn base64() {
+    autoreleasepool(|pool| {
+        let data = NSData::from_vec(b"hello, world".to_vec(), pool);
+        let encoded = data.base64EncodedStringWithOptions(NSDataBase64EncodingOptions::default(), pool);
+        assert_eq!(encoded.to_str(pool), "aGVsbG8sIHdvcmxk");
+        let decoded = NSData::withBase64EncodedString(&encoded, NSDataBase64DecodingOptions::default(), pool).unwrap();
+        assert_eq!(decoded.as_slice(pool), b"hello, world");
+
+        let long = NSData::from_vec(vec![0; 60], pool);
+        let wrapped = long.base64EncodedStringWithOptions(NSDataBase64EncodingOptions::LINE_LENGTH_64 | NSDataBase64EncodingOptions::END_LINE_WITH_LINE_FEED, pool);
+        assert_eq!(wrapped.to_str(pool).lines().map(|line| line.len()).collect::<Vec<_>>(), [64, 16]);
+        assert!(NSData::withBase64EncodedString(&wrapped, NSDataBase64DecodingOptions::default(), pool).is_none());
+        let unwrapped = NSData::withBase64EncodedString(&wrapped, NSDataBase64DecodingOptions::IGNORE_UNKNOWN_CHARACTERS, pool).unwrap();
+        assert_eq!(unwrapped.as_slice(pool), &[0; 60]);
+    })
+}
+
+#[test] fn compression() {
+    autoreleasepool(|pool| {
+        let data = NSData::from_vec(b"compress me ".repeat(100), pool);
+        for algorithm in [NSDataCompressionAlgorithm::LZFSE, NSDataCompressionAlgorithm::LZ4, NSDataCompressionAlgorithm::LZMA, NSDataCompressionAlgorithm::Zlib] {
+            let compressed = data.compressedDataUsingAlgorithm(algorithm, pool).unwrap();
+            assert!(compressed.length(pool) < data.length(pool));
+            let decompressed = compressed.decompressedDataUsingAlgorithm(algorithm, pool).unwrap();
+            assert_eq!(decompressed.as_slice(pool), data.as_slice(pool));
+        }
+        let garbage = NSData::from_vec(vec![0xff; 32], pool);
+        assert!(garbage.decompressedDataUsingAlgorithm(NSDataCompressionAlgorithm::LZMA, pool).is_err());
+    })
+}
@@ -1,6 +1,238 @@
 use objr::bindings::*;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::NSComparisonResult;
+
 objc_class! {
     pub struct NSDate {
         @class(NSDate)
     }
-}
\ No newline at end of file
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("date")
+        @selector("dateWithTimeIntervalSinceNow:")
+        @selector("dateWithTimeIntervalSince1970:")
+        @selector("dateWithTimeIntervalSinceReferenceDate:")
+        @selector("distantFuture")
+        @selector("distantPast")
+        @selector("timeIntervalSinceReferenceDate")
+        @selector("timeIntervalSince1970")
+        @selector("timeIntervalSinceNow")
+        @selector("timeIntervalSinceDate:")
+        @selector("dateByAddingTimeInterval:")
+        @selector("compare:")
+        @selector("earlierDate:")
+        @selector("laterDate:")
+        @selector("isEqualToDate:")
+    }
+    impl Selectors for Sel {}
+}
+
+///`NSTimeInterval`, in seconds.
+pub type NSTimeInterval = f64;
+
+///Seconds from the Unix epoch to the reference date, 2001-01-01 00:00:00 UTC.
+#[allow(non_upper_case_globals)]
+pub const NSTimeIntervalSince1970: NSTimeInterval = 978307200.0;
+
+///The reference date as a `SystemTime`.
+pub fn reference_date() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(NSTimeIntervalSince1970 as u64)
+}
+
+///Seconds from the reference date to `time`.
+pub fn reference_interval_from_system_time(time: SystemTime) -> NSTimeInterval {
+    //measure from the reference date directly, since going through the Unix epoch loses precision
+    match time.duration_since(reference_date()) {
+        Ok(after) => after.as_secs_f64(),
+        Err(before) => -before.duration().as_secs_f64(),
+    }
+}
+
+///The interval can't be represented as a `SystemTime`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NSDateRangeError(pub NSTimeInterval);
+impl Display for NSDateRangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} seconds from the reference date is out of range for SystemTime", self.0)
+    }
+}
+impl std::error::Error for NSDateRangeError {}
+
+///The `SystemTime` `interval` seconds from the reference date.
+pub fn system_time_from_reference_interval(interval: NSTimeInterval) -> Result<SystemTime, NSDateRangeError> {
+    let error = NSDateRangeError(interval);
+    if interval >= 0.0 {
+        let duration = Duration::try_from_secs_f64(interval).map_err(|_| error)?;
+        reference_date().checked_add(duration).ok_or(error)
+    }
+    else {
+        let duration = Duration::try_from_secs_f64(-interval).map_err(|_| error)?;
+        reference_date().checked_sub(duration).ok_or(error)
+    }
+}
+
+/**
+`NSDate`.
+
+Rust's orphan rules don't allow `From<SystemTime> for StrongCell<NSDate>`, and creating a date needs an autorelease pool anyway.
+Use [NSDate::from_system_time] to convert from `SystemTime`, and `SystemTime::try_from(&date)` or [NSDate::to_system_time]
+to convert back.
+*/
+#[allow(non_snake_case)]
+impl NSDate {
+    fn class_date(sel: Sel, interval: Option<NSTimeInterval>, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = match interval {
+                Some(interval) => Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), sel, pool, (interval,)),
+                None => Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), sel, pool, ()),
+            };
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///The current date.
+    pub fn now(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::class_date(Sel::date(), None, pool)
+    }
+    pub fn dateWithTimeIntervalSinceNow(interval: NSTimeInterval, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::class_date(Sel::dateWithTimeIntervalSinceNow_(), Some(interval), pool)
+    }
+    pub fn dateWithTimeIntervalSince1970(interval: NSTimeInterval, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::class_date(Sel::dateWithTimeIntervalSince1970_(), Some(interval), pool)
+    }
+    pub fn dateWithTimeIntervalSinceReferenceDate(interval: NSTimeInterval, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::class_date(Sel::dateWithTimeIntervalSinceReferenceDate_(), Some(interval), pool)
+    }
+    ///A date in the distant future, for "never".
+    pub fn distantFuture(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::class_date(Sel::distantFuture(), None, pool)
+    }
+    ///A date in the distant past.
+    pub fn distantPast(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::class_date(Sel::distantPast(), None, pool)
+    }
+    pub fn from_system_time(time: SystemTime, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::dateWithTimeIntervalSinceReferenceDate(reference_interval_from_system_time(time), pool)
+    }
+
+    pub fn timeIntervalSinceReferenceDate(&self, pool: &ActiveAutoreleasePool) -> NSTimeInterval {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::timeIntervalSinceReferenceDate(), pool, ())
+        }
+    }
+    pub fn timeIntervalSince1970(&self, pool: &ActiveAutoreleasePool) -> NSTimeInterval {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::timeIntervalSince1970(), pool, ())
+        }
+    }
+    ///Negative if the receiver is in the past.
+    pub fn timeIntervalSinceNow(&self, pool: &ActiveAutoreleasePool) -> NSTimeInterval {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::timeIntervalSinceNow(), pool, ())
+        }
+    }
+    ///Negative if the receiver is earlier than `other`.
+    pub fn timeIntervalSinceDate(&self, other: &NSDate, pool: &ActiveAutoreleasePool) -> NSTimeInterval {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::timeIntervalSinceDate_(), pool, (other.assume_nonmut_perform(),))
+        }
+    }
+    pub fn dateByAddingTimeInterval(&self, interval: NSTimeInterval, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::dateByAddingTimeInterval_(), pool, (interval,));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn date_by_adding(&self, duration: Duration, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        self.dateByAddingTimeInterval(duration.as_secs_f64(), pool)
+    }
+    pub fn date_by_subtracting(&self, duration: Duration, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        self.dateByAddingTimeInterval(-duration.as_secs_f64(), pool)
+    }
+    ///The time from `earlier` to the receiver, or `None` if `earlier` is later than the receiver.
+    pub fn duration_since(&self, earlier: &NSDate, pool: &ActiveAutoreleasePool) -> Option<Duration> {
+        Duration::try_from_secs_f64(self.timeIntervalSinceDate(earlier, pool)).ok()
+    }
+
+    pub fn compare(&self, other: &NSDate, pool: &ActiveAutoreleasePool) -> NSComparisonResult {
+        unsafe {
+            NSComparisonResult::from_raw(Self::perform_primitive(self.assume_nonmut_perform(), Sel::compare_(), pool, (other.assume_nonmut_perform(),)))
+        }
+    }
+    pub fn earlierDate(&self, other: &NSDate, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::earlierDate_(), pool, (other.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn laterDate(&self, other: &NSDate, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::laterDate_(), pool, (other.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn isEqualToDate(&self, other: &NSDate, pool: &ActiveAutoreleasePool) -> bool {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::isEqualToDate_(), pool, (other.assume_nonmut_perform(),))
+        }
+    }
+    pub fn to_system_time(&self, pool: &ActiveAutoreleasePool) -> Result<SystemTime, NSDateRangeError> {
+        system_time_from_reference_interval(self.timeIntervalSinceReferenceDate(pool))
+    }
+}
+
+///Uses a temporary autorelease pool.  If you have one, [NSDate::to_system_time] is cheaper.
+impl TryFrom<&NSDate> for SystemTime {
+    type Error = NSDateRangeError;
+    fn try_from(date: &NSDate) -> Result<Self, Self::Error> {
+        autoreleasepool(|pool| date.to_system_time(pool))
+    }
+}
+
+#[test] fn reference_date_math() {
+    assert_eq!(reference_date().duration_since(UNIX_EPOCH).unwrap(), Duration::from_secs(978307200));
+    assert_eq!(reference_interval_from_system_time(reference_date()), 0.0);
+    assert_eq!(reference_interval_from_system_time(UNIX_EPOCH), -NSTimeIntervalSince1970);
+    let later = reference_date() + Duration::from_millis(1500);
+    assert_eq!(reference_interval_from_system_time(later), 1.5);
+    assert_eq!(system_time_from_reference_interval(1.5), Ok(later));
+    assert_eq!(system_time_from_reference_interval(-NSTimeIntervalSince1970), Ok(UNIX_EPOCH));
+
+    //sub-microsecond precision survives the round trip near the present
+    let now = reference_date() + Duration::new(700_000_000, 123_456_000);
+    let back = system_time_from_reference_interval(reference_interval_from_system_time(now)).unwrap();
+    let error = back.duration_since(now).unwrap_or_else(|e| e.duration());
+    assert!(error < Duration::from_micros(1));
+
+    assert!(system_time_from_reference_interval(f64::NAN).is_err());
+    assert!(system_time_from_reference_interval(f64::INFINITY).is_err());
+    assert_eq!(system_time_from_reference_interval(f64::NEG_INFINITY), Err(NSDateRangeError(f64::NEG_INFINITY)));
+}
+
+#[test] fn dates() {
+    autoreleasepool(|pool| {
+        let epoch = NSDate::dateWithTimeIntervalSince1970(0.0, pool);
+        assert_eq!(epoch.timeIntervalSinceReferenceDate(pool), -NSTimeIntervalSince1970);
+        assert_eq!(epoch.to_system_time(pool), Ok(UNIX_EPOCH));
+        assert_eq!(SystemTime::try_from(&*epoch), Ok(UNIX_EPOCH));
+
+        let later = epoch.date_by_adding(Duration::from_secs(60), pool);
+        assert_eq!(later.timeIntervalSince1970(pool), 60.0);
+        assert_eq!(later.duration_since(&epoch, pool), Some(Duration::from_secs(60)));
+        assert_eq!(epoch.duration_since(&later, pool), None);
+        assert!(later.date_by_subtracting(Duration::from_secs(60), pool).isEqualToDate(&epoch, pool));
+
+        assert_eq!(epoch.compare(&later, pool), NSComparisonResult::OrderedAscending);
+        assert!(epoch.earlierDate(&later, pool).isEqualToDate(&epoch, pool));
+        assert!(epoch.laterDate(&later, pool).isEqualToDate(&later, pool));
+
+        let now = NSDate::now(pool);
+        assert!(NSDate::distantPast(pool).compare(&now, pool) == NSComparisonResult::OrderedAscending);
+        assert!(NSDate::distantFuture(pool).timeIntervalSinceNow(pool) > 0.0);
+        assert!(NSDate::dateWithTimeIntervalSinceNow(-10.0, pool).timeIntervalSinceNow(pool) < 0.0);
+
+        let time = reference_date() + Duration::from_millis(250);
+        assert_eq!(NSDate::from_system_time(time, pool).timeIntervalSinceReferenceDate(pool), 0.25);
+    })
+}
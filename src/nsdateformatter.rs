@@ -0,0 +1,251 @@
+use objr::bindings::*;
+use std::ops::BitOr;
+use crate::{NSDate, NSLocale, NSTimeZone, NSUInteger};
+
+objc_class! {
+    pub struct NSDateFormatter {
+        @class(NSDateFormatter)
+    }
+}
+objc_class! {
+    pub struct NSISO8601DateFormatter {
+        @class(NSISO8601DateFormatter)
+    }
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("dateFormat")
+        @selector("setDateFormat:")
+        @selector("locale")
+        @selector("setLocale:")
+        @selector("timeZone")
+        @selector("setTimeZone:")
+        @selector("stringFromDate:")
+        @selector("dateFromString:")
+        @selector("formatOptions")
+        @selector("setFormatOptions:")
+        @selector("stringFromDate:timeZone:formatOptions:")
+    }
+    impl Selectors for Sel {}
+}
+
+///`NSISO8601DateFormatOptions`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSISO8601DateFormatOptions(pub NSUInteger);
+impl NSISO8601DateFormatOptions {
+    pub const WITH_YEAR: Self = Self(1 << 0);
+    pub const WITH_MONTH: Self = Self(1 << 1);
+    pub const WITH_WEEK_OF_YEAR: Self = Self(1 << 2);
+    pub const WITH_DAY: Self = Self(1 << 4);
+    pub const WITH_TIME: Self = Self(1 << 5);
+    pub const WITH_TIME_ZONE: Self = Self(1 << 6);
+    pub const WITH_SPACE_BETWEEN_DATE_AND_TIME: Self = Self(1 << 7);
+    pub const WITH_DASH_SEPARATOR_IN_DATE: Self = Self(1 << 8);
+    pub const WITH_COLON_SEPARATOR_IN_TIME: Self = Self(1 << 9);
+    pub const WITH_COLON_SEPARATOR_IN_TIME_ZONE: Self = Self(1 << 10);
+    pub const WITH_FRACTIONAL_SECONDS: Self = Self(1 << 11);
+
+    ///`2001-01-01`
+    pub const WITH_FULL_DATE: Self = Self(Self::WITH_YEAR.0 | Self::WITH_MONTH.0 | Self::WITH_DAY.0 | Self::WITH_DASH_SEPARATOR_IN_DATE.0);
+    ///`00:00:00Z`
+    pub const WITH_FULL_TIME: Self = Self(Self::WITH_TIME.0 | Self::WITH_COLON_SEPARATOR_IN_TIME.0 | Self::WITH_TIME_ZONE.0 | Self::WITH_COLON_SEPARATOR_IN_TIME_ZONE.0);
+    ///RFC 3339, such as `2001-01-01T00:00:00Z`.  This is the default.
+    pub const WITH_INTERNET_DATE_TIME: Self = Self(Self::WITH_FULL_DATE.0 | Self::WITH_FULL_TIME.0);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+impl BitOr for NSISO8601DateFormatOptions {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+///The `dateFormat` for RFC 1123 dates, as used in HTTP `Date` headers.  Use it with [NSLocale::en_us_posix] and GMT.
+pub const RFC1123_DATE_FORMAT: &str = "EEE, dd MMM yyyy HH:mm:ss 'GMT'";
+
+#[allow(non_snake_case)]
+impl NSDateFormatter {
+    pub fn new(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            Self::class().alloc_init(pool).assume_mut()
+        }
+    }
+    /**
+    A formatter for RFC 1123 dates, such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+
+    Formatters are expensive to create, so reuse this if you format many dates.
+    */
+    pub fn rfc1123(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        let mut formatter = Self::new(pool);
+        formatter.setLocale(Some(&NSLocale::en_us_posix(pool)), pool);
+        formatter.setTimeZone(Some(&NSTimeZone::gmt(pool)), pool);
+        formatter.setDateFormat(&NSString::with_str_copy(RFC1123_DATE_FORMAT, pool), pool);
+        formatter
+    }
+    pub fn dateFormat(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::dateFormat(), pool, ());
+            NSString::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///A format pattern such as `yyyy-MM-dd`.  For fixed formats, also set the locale to [NSLocale::en_us_posix].
+    pub fn setDateFormat(&mut self, format: &NSString, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setDateFormat_(), pool, (format.assume_nonmut_perform(),))
+        }
+    }
+    pub fn locale(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSLocale> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::locale(), pool, ());
+            NSLocale::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///`None` resets to the current locale.
+    pub fn setLocale(&mut self, locale: Option<&NSLocale>, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setLocale_(), pool, (locale.assume_nonmut_perform(),))
+        }
+    }
+    pub fn timeZone(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSTimeZone> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::timeZone(), pool, ());
+            NSTimeZone::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///`None` resets to the system time zone.
+    pub fn setTimeZone(&mut self, time_zone: Option<&NSTimeZone>, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setTimeZone_(), pool, (time_zone.assume_nonmut_perform(),))
+        }
+    }
+    pub fn stringFromDate(&self, date: &NSDate, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::stringFromDate_(), pool, (date.assume_nonmut_perform(),));
+            NSString::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///`None` if `string` doesn't match the format.
+    pub fn dateFromString(&self, string: &NSString, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSDate>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::dateFromString_(), pool, (string.assume_nonmut_perform(),));
+            NSDate::nullable(raw).assume_retained()
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+impl NSDate {
+    ///Formats the date for an HTTP header, such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+    pub fn rfc1123_string(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        NSDateFormatter::rfc1123(pool).stringFromDate(self, pool)
+    }
+    ///Parses a date formatted like [NSDate::rfc1123_string], or `None` if `string` isn't one.
+    pub fn from_rfc1123_string(string: &NSString, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSDate>> {
+        NSDateFormatter::rfc1123(pool).dateFromString(string, pool)
+    }
+}
+
+#[allow(non_snake_case)]
+impl NSISO8601DateFormatter {
+    ///Formats RFC 3339 dates in GMT, such as `2001-01-01T00:00:00Z`.
+    pub fn new(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            Self::class().alloc_init(pool).assume_mut()
+        }
+    }
+    pub fn formatOptions(&self, pool: &ActiveAutoreleasePool) -> NSISO8601DateFormatOptions {
+        unsafe {
+            NSISO8601DateFormatOptions(Self::perform_primitive(self.assume_nonmut_perform(), Sel::formatOptions(), pool, ()))
+        }
+    }
+    pub fn setFormatOptions(&mut self, options: NSISO8601DateFormatOptions, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setFormatOptions_(), pool, (options.0,))
+        }
+    }
+    pub fn timeZone(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSTimeZone> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::timeZone(), pool, ());
+            NSTimeZone::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///`None` resets to GMT.
+    pub fn setTimeZone(&mut self, time_zone: Option<&NSTimeZone>, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setTimeZone_(), pool, (time_zone.assume_nonmut_perform(),))
+        }
+    }
+    pub fn stringFromDate(&self, date: &NSDate, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::stringFromDate_(), pool, (date.assume_nonmut_perform(),));
+            NSString::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///`None` if `string` doesn't match the format options.
+    pub fn dateFromString(&self, string: &NSString, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSDate>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::dateFromString_(), pool, (string.assume_nonmut_perform(),));
+            NSDate::nullable(raw).assume_retained()
+        }
+    }
+    ///Formats without creating a formatter.
+    pub fn stringFromDateTimeZoneFormatOptions(date: &NSDate, time_zone: &NSTimeZone, options: NSISO8601DateFormatOptions, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::stringFromDate_timeZone_formatOptions(), pool, (date.assume_nonmut_perform(), time_zone.assume_nonmut_perform(), options.0));
+            NSString::assume_nonnil(raw).assume_retained()
+        }
+    }
+}
+
+#[test] fn date_formatter() {
+    autoreleasepool(|pool| {
+        let mut formatter = NSDateFormatter::new(pool);
+        formatter.setLocale(Some(&NSLocale::en_us_posix(pool)), pool);
+        formatter.setTimeZone(Some(&NSTimeZone::gmt(pool)), pool);
+        formatter.setDateFormat(objc_nsstring!("yyyy-MM-dd HH:mm"), pool);
+        assert_eq!(formatter.dateFormat(pool).to_str(pool), "yyyy-MM-dd HH:mm");
+        assert_eq!(formatter.locale(pool).localeIdentifier(pool).to_str(pool), "en_US_POSIX");
+        assert_eq!(formatter.timeZone(pool).name(pool).to_str(pool), "GMT");
+
+        let date = NSDate::dateWithTimeIntervalSince1970(86400.0 + 3600.0, pool);
+        assert_eq!(formatter.stringFromDate(&date, pool).to_str(pool), "1970-01-02 01:00");
+        let parsed = formatter.dateFromString(objc_nsstring!("1970-01-02 01:00"), pool).unwrap();
+        assert!(parsed.isEqualToDate(&date, pool));
+        assert!(formatter.dateFromString(objc_nsstring!("not a date"), pool).is_none());
+    })
+}
+
+#[test] fn rfc1123() {
+    autoreleasepool(|pool| {
+        let date = NSDate::dateWithTimeIntervalSince1970(784111777.0, pool);
+        assert_eq!(date.rfc1123_string(pool).to_str(pool), "Sun, 06 Nov 1994 08:49:37 GMT");
+        let parsed = NSDate::from_rfc1123_string(objc_nsstring!("Sun, 06 Nov 1994 08:49:37 GMT"), pool).unwrap();
+        assert_eq!(parsed.timeIntervalSince1970(pool), 784111777.0);
+        assert!(NSDate::from_rfc1123_string(objc_nsstring!("1994-11-06"), pool).is_none());
+    })
+}
+
+#[test] fn iso8601() {
+    autoreleasepool(|pool| {
+        let mut formatter = NSISO8601DateFormatter::new(pool);
+        assert_eq!(formatter.formatOptions(pool), NSISO8601DateFormatOptions::WITH_INTERNET_DATE_TIME);
+        let date = NSDate::dateWithTimeIntervalSinceReferenceDate(0.5, pool);
+        assert_eq!(formatter.stringFromDate(&date, pool).to_str(pool), "2001-01-01T00:00:00Z");
+
+        formatter.setFormatOptions(NSISO8601DateFormatOptions::WITH_INTERNET_DATE_TIME | NSISO8601DateFormatOptions::WITH_FRACTIONAL_SECONDS, pool);
+        assert_eq!(formatter.stringFromDate(&date, pool).to_str(pool), "2001-01-01T00:00:00.500Z");
+        let parsed = formatter.dateFromString(objc_nsstring!("2001-01-01T00:00:00.500Z"), pool).unwrap();
+        assert_eq!(parsed.timeIntervalSinceReferenceDate(pool), 0.5);
+
+        let chicago = NSTimeZone::timeZoneWithName(objc_nsstring!("America/Chicago"), pool).unwrap();
+        formatter.setTimeZone(Some(&chicago), pool);
+        formatter.setFormatOptions(NSISO8601DateFormatOptions::WITH_FULL_DATE, pool);
+        assert_eq!(formatter.stringFromDate(&date, pool).to_str(pool), "2000-12-31");
+
+        let string = NSISO8601DateFormatter::stringFromDateTimeZoneFormatOptions(&date, &NSTimeZone::gmt(pool), NSISO8601DateFormatOptions::WITH_FULL_TIME, pool);
+        assert_eq!(string.to_str(pool), "00:00:00Z");
+    })
+}
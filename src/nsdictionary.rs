@@ -1,7 +1,11 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 use objr::bindings::*;
 use crate::nscopying::NSCopying;
-use crate::NSUInteger;
-use std::fmt::{Debug};
+use crate::nsarray::{with_comparator, with_scoped_closure};
+use crate::nsenumerator::{FastEnumerator, NSFastEnumeration, NSFastEnumerationState, WithPool};
+use crate::{NSArray, NSStringError, NSStringExtension, NSUInteger};
+use std::fmt::{Debug, Display, Formatter};
 
 objc_class! {
     pub struct NSDictionaryRaw {
@@ -16,21 +20,111 @@ objc_class_newtype! {
 objc_selector_group! {
     trait NSDictionarySelectors {
         @selector("initWithObjects:forKeys:count:")
+        @selector("initWithDictionary:")
         @selector("objectForKey:")
+        @selector("count")
+        @selector("allKeys")
+        @selector("allValues")
+        @selector("countByEnumeratingWithState:objects:count:")
+        @selector("enumerateKeysAndObjectsUsingBlock:")
+        @selector("keysSortedByValueUsingComparator:")
+        @selector("hash")
+        @selector("isEqual:")
     }
     impl NSDictionarySelectors for Sel {}
 }
 
+blocksr::many_escaping_nonsend!(KeysAndObjectsBlock(key: *const NSObject, obj: *const NSObject, stop: *mut bool) -> ());
+unsafe impl Arguable for &KeysAndObjectsBlock {}
+
+///Errors creating an [NSDictionary].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NSDictionaryError {
+    ///The number of objects and keys differ.
+    LengthMismatch { objects: usize, keys: usize },
+}
+impl Display for NSDictionaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NSDictionaryError::LengthMismatch { objects, keys } => write!(f, "{} objects but {} keys", objects, keys),
+        }
+    }
+}
+impl std::error::Error for NSDictionaryError {}
+
+/**
+A retained dictionary key for Rust collections.  It hashes and compares with `-hash` and `-isEqual:`, as
+`NSDictionary` does, so keys that are equal in ObjC are equal here even if they are different objects.
+
+Used by [NSDictionary::to_hash_map] and [NSDictionary::from_hash_map].
+*/
+pub struct NSDictionaryKey<Key: ObjcInstance>(pub StrongCell<Key>);
+impl<Key: ObjcInstance> NSDictionaryKey<Key> {
+    ///Retains `key`.
+    pub fn new(key: &Key) -> Self {
+        NSDictionaryKey(key.retain())
+    }
+    pub fn into_inner(self) -> StrongCell<Key> {
+        self.0
+    }
+}
+impl<Key: ObjcInstance> std::ops::Deref for NSDictionaryKey<Key> {
+    type Target = Key;
+    fn deref(&self) -> &Key {
+        &self.0
+    }
+}
+impl<Key: ObjcInstance> std::hash::Hash for NSDictionaryKey<Key> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        //-hash doesn't autorelease, so a local pool costs little
+        let hash: NSUInteger = autoreleasepool(|pool| unsafe {
+            Key::perform_primitive(self.0.assume_nonmut_perform(), Sel::hash(), pool, ())
+        });
+        hash.hash(state)
+    }
+}
+impl<Key: ObjcInstance> PartialEq for NSDictionaryKey<Key> {
+    fn eq(&self, other: &Self) -> bool {
+        autoreleasepool(|pool| unsafe {
+            Key::perform_primitive(self.0.assume_nonmut_perform(), Sel::isEqual_(), pool, (other.0.assume_nonmut_perform(),))
+        })
+    }
+}
+impl<Key: ObjcInstance> Eq for NSDictionaryKey<Key> {}
+
 #[allow(non_snake_case)]
-impl<Key: NSCopying + 'static + Debug,Value: ObjcInstance + 'static> NSDictionary<Key,Value> {
+impl<Key: NSCopying + 'static,Value: ObjcInstance + 'static> NSDictionary<Key,Value> {
+    ///# Panics
+    /// If `objects` and `keys` have different lengths.  See [Self::try_withObjectsForKeys] and [Self::from_pairs].
     pub fn withObjectsForKeys(objects:&[&Value],keys:&[&Key], pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
-        assert_eq!(objects.len(),keys.len());
+        match Self::try_withObjectsForKeys(objects, keys, pool) {
+            Ok(dictionary) => dictionary,
+            Err(e) => panic!("{}", e)
+        }
+    }
+    pub fn try_withObjectsForKeys(objects:&[&Value],keys:&[&Key], pool: &ActiveAutoreleasePool) -> Result<StrongCell<Self>, NSDictionaryError> {
+        if objects.len() != keys.len() {
+            return Err(NSDictionaryError::LengthMismatch { objects: objects.len(), keys: keys.len() })
+        }
         unsafe {
             let s = Self::class().alloc(pool);
             let key_ptr: *const Key = std::mem::transmute(keys.as_ptr());
             let value_ptr: *const Value = std::mem::transmute(objects.as_ptr());
             let s = Self::perform(s,Sel::initWithObjects_forKeys_count(), pool, (value_ptr.assume_nonmut_perform(), key_ptr.assume_nonmut_perform(), objects.len() as NSUInteger));
-            Self::assume_nonnil(s).assume_retained()
+            Ok(Self::assume_nonnil(s).assume_retained())
+        }
+    }
+    ///Creates a dictionary from `(key, value)` pairs.  If a key appears more than once, which value is kept is unspecified.
+    pub fn from_pairs(pairs: &[(&Key,&Value)], pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        let (keys, values): (Vec<&Key>, Vec<&Value>) = pairs.iter().copied().unzip();
+        Self::withObjectsForKeys(&values, &keys, pool)
+    }
+    ///Copies `other`, which may be an [NSMutableDictionary] cast with [NSMutableDictionary::as_immutable].
+    pub fn withDictionary(other: &NSDictionary<Key,Value>, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithDictionary_(), pool, (other.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
         }
     }
     pub fn objectForKey(&self, key: &Key, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Value>> {
@@ -39,6 +133,291 @@ impl<Key: NSCopying + 'static + Debug,Value: ObjcInstance + 'static> NSDictionar
             Value::nullable(ptr).assume_retained()
         }
     }
+    pub fn count(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::count(), pool, ())
+        }
+    }
+    pub fn allKeys(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSArray<Key>> {
+        unsafe {
+            let ptr = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::allKeys(), pool, ());
+            NSArray::assume_nonnil(ptr).assume_retained()
+        }
+    }
+    pub fn allValues(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSArray<Value>> {
+        unsafe {
+            let ptr = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::allValues(), pool, ());
+            NSArray::assume_nonnil(ptr).assume_retained()
+        }
+    }
+    ///Fast-enumerates the keys.
+    pub fn iter<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> FastEnumerator<&Self> {
+        FastEnumerator::new(self, pool)
+    }
+    ///For use with `for key in dictionary.with_pool(pool)`.
+    pub fn with_pool<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> WithPool<'a, Self> {
+        WithPool::new(self, pool)
+    }
+    /**
+    Calls `f` with each key and value.  Set the `bool` to `true` to stop early.
+
+    `f` is called synchronously and not retained.  A panic in `f` aborts the process, since it can't unwind through ObjC.
+    */
+    pub fn enumerateKeysAndObjectsUsingBlock<F: FnMut(&Key, &Value, &mut bool)>(&self, mut f: F, pool: &ActiveAutoreleasePool) {
+        let adapter = move |(key, obj, stop): (*const NSObject, *const NSObject, *mut bool)| {
+            //safety: the dictionary only contains Key and Value
+            unsafe { f(&*(key as *const Key), &*(obj as *const Value), &mut *stop) }
+        };
+        //safety: enumerateKeysAndObjectsUsingBlock: doesn't keep the block
+        unsafe {
+            with_scoped_closure(adapter, |scoped| {
+                let block = KeysAndObjectsBlock::new(move |key, obj, stop| scoped.call((key, obj, stop)));
+                Self::perform_primitive(self.assume_nonmut_perform(), Sel::enumerateKeysAndObjectsUsingBlock_(), pool, (&block,))
+            })
+        }
+    }
+    ///The keys, in the order that sorts their values by `comparator`.  A panic in `comparator` aborts the process.
+    pub fn keysSortedByValueUsingComparator<F: FnMut(&Value, &Value) -> Ordering>(&self, comparator: F, pool: &ActiveAutoreleasePool) -> StrongCell<NSArray<Key>> {
+        //safety: keysSortedByValueUsingComparator: doesn't keep the block, and it only compares values
+        unsafe {
+            with_comparator(comparator, |block| {
+                let ptr = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::keysSortedByValueUsingComparator_(), pool, (block,));
+                NSArray::assume_nonnil(ptr).assume_retained()
+            })
+        }
+    }
+
+    ///Retains every key and value into a `HashMap`, which can be used outside the autorelease pool.
+    pub fn to_hash_map(&self, pool: &ActiveAutoreleasePool) -> HashMap<NSDictionaryKey<Key>, StrongCell<Value>> {
+        let mut map = HashMap::with_capacity(self.count(pool) as usize);
+        self.enumerateKeysAndObjectsUsingBlock(|key, value, _| {
+            map.insert(NSDictionaryKey::new(key), value.retain());
+        }, pool);
+        map
+    }
+    pub fn from_hash_map<V: std::ops::Deref<Target=Value>, S>(map: &HashMap<NSDictionaryKey<Key>, V, S>, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        let (keys, values): (Vec<&Key>, Vec<&Value>) = map.iter().map(|(k, v)| (&**k, &**v)).unzip();
+        Self::withObjectsForKeys(&values, &keys, pool)
+    }
+}
+
+impl<Value: ObjcInstance + 'static> NSDictionary<NSString, Value> {
+    /**
+    Copies the keys into Rust strings, and retains the values.
+
+    Fails if a key contains unpaired UTF-16 surrogates, which have no UTF-8 form.  Use [Self::to_hash_map] for those.
+    */
+    pub fn to_btree_map(&self, pool: &ActiveAutoreleasePool) -> Result<BTreeMap<String, StrongCell<Value>>, NSStringError> {
+        let mut map = BTreeMap::new();
+        let mut result = Ok(());
+        self.enumerateKeysAndObjectsUsingBlock(|key, value, stop| {
+            match key.try_to_string(pool) {
+                Ok(key) => { map.insert(key, value.retain()); }
+                Err(e) => {
+                    result = Err(e);
+                    *stop = true;
+                }
+            }
+        }, pool);
+        result.map(|_| map)
+    }
+    pub fn from_btree_map<V: std::ops::Deref<Target=Value>>(map: &BTreeMap<String, V>, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        let keys: Vec<StrongCell<NSString>> = map.keys().map(|k| NSString::with_str_copy(k, pool)).collect();
+        let keys: Vec<&NSString> = keys.iter().map(|k| &**k).collect();
+        let values: Vec<&Value> = map.values().map(|v| &**v).collect();
+        Self::withObjectsForKeys(&values, &keys, pool)
+    }
+}
+
+impl<Key: Arguable + 'static, Value: 'static> NSFastEnumeration for &NSDictionary<Key, Value> {
+    type Element = Key;
+    fn countByEnumeratingWithStateObjectsCount(self, state: &mut NSFastEnumerationState, objects: *mut *const Self::Element, count: NSUInteger, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        unsafe {
+            //the equivalent of assume_nonmut_perform for this argument
+            let transmut: *mut *mut Key = std::mem::transmute(objects);
+            NSDictionary::perform_primitive(self.assume_nonmut_perform(), Sel::countByEnumeratingWithState_objects_count(), pool, (state, transmut, count))
+        }
+    }
+    fn count_hint(self, pool: &ActiveAutoreleasePool) -> Option<NSUInteger> {
+        unsafe {
+            Some(NSDictionary::perform_primitive(self.assume_nonmut_perform(), Sel::count(), pool, ()))
+        }
+    }
+}
+impl<'e, Key: Arguable + 'static, Value: 'static> ExactSizeIterator for FastEnumerator<'e, &NSDictionary<Key, Value>> {}
+impl<'a, Key: Arguable + 'static, Value: 'static> IntoIterator for WithPool<'a, NSDictionary<Key, Value>> {
+    type Item = &'a Key;
+    type IntoIter = FastEnumerator<'a, &'a NSDictionary<Key, Value>>;
+    fn into_iter(self) -> Self::IntoIter {
+        FastEnumerator::new(self.value(), self.pool())
+    }
+}
+
+
+objc_class! {
+    pub struct NSMutableDictionaryRaw {
+        @class(NSMutableDictionary)
+    }
+}
+
+objc_class_newtype! {
+    pub struct NSMutableDictionary <Key,Value>: NSMutableDictionaryRaw;
+}
+
+objc_selector_group! {
+    trait NSMutableDictionarySelectors {
+        @selector("initWithCapacity:")
+        @selector("setObject:forKey:")
+        @selector("removeObjectForKey:")
+        @selector("addEntriesFromDictionary:")
+        @selector("removeAllObjects")
+    }
+    impl NSMutableDictionarySelectors for Sel {}
+}
+
+/**
+`NSMutableDictionary`.
+
+`setObject:forKey:` copies the key, which is why keys must be [NSCopying].
+*/
+#[allow(non_snake_case)]
+impl<Key: NSCopying + 'static,Value: ObjcInstance + 'static> NSMutableDictionary<Key,Value> {
+    pub fn new(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            Self::class().alloc_init(pool).assume_mut()
+        }
+    }
+    pub fn withCapacity(capacity: NSUInteger, pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithCapacity_(), pool, (capacity,));
+            Self::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    pub fn as_immutable(&self) -> &NSDictionary<Key,Value> {
+        unsafe { self.cast() }
+    }
+    pub fn count(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        self.as_immutable().count(pool)
+    }
+    pub fn objectForKey(&self, key: &Key, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Value>> {
+        self.as_immutable().objectForKey(key, pool)
+    }
+    ///Fast-enumerates the keys.
+    pub fn iter<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> FastEnumerator<&NSDictionary<Key,Value>> {
+        self.as_immutable().iter(pool)
+    }
+    pub fn with_pool<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> WithPool<'a, Self> {
+        WithPool::new(self, pool)
+    }
+    ///Adds the entry, replacing any existing value for `key`.
+    pub fn setObjectForKey(&mut self, object: &Value, key: &Key, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setObject_forKey(), pool, (object.assume_nonmut_perform(), key.assume_nonmut_perform()))
+        }
+    }
+    ///Does nothing if `key` is not present.
+    pub fn removeObjectForKey(&mut self, key: &Key, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::removeObjectForKey_(), pool, (key.assume_nonmut_perform(),))
+        }
+    }
+    ///Adds every entry of `other`, replacing the values of existing keys.
+    pub fn addEntriesFromDictionary(&mut self, other: &NSDictionary<Key,Value>, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::addEntriesFromDictionary_(), pool, (other.assume_nonmut_perform(),))
+        }
+    }
+    pub fn removeAllObjects(&mut self, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::removeAllObjects(), pool, ())
+        }
+    }
+}
+impl<'a, Key: Arguable + 'static, Value: 'static> IntoIterator for WithPool<'a, NSMutableDictionary<Key, Value>> {
+    type Item = &'a Key;
+    type IntoIter = FastEnumerator<'a, &'a NSDictionary<Key, Value>>;
+    fn into_iter(self) -> Self::IntoIter {
+        let immutable: &'a NSDictionary<Key, Value> = unsafe { self.value().cast() };
+        FastEnumerator::new(immutable, self.pool())
+    }
+}
+
+/**
+Collects entries for an [NSDictionary] or [NSMutableDictionary].
+
+Creating a dictionary needs an autorelease pool, which [FromIterator] can't supply, so collect into a builder
+and then call [Self::build] or [Self::build_mut].
+
+```ignore
+let builder: NSDictionaryBuilder<NSString, NSString> = pairs.into_iter().collect();
+let dictionary = builder.build(pool);
+```
+
+Later entries replace earlier entries with an equal key.
+*/
+pub struct NSDictionaryBuilder<'a, Key, Value> {
+    keys: Vec<&'a Key>,
+    values: Vec<&'a Value>,
+}
+impl<'a, Key, Value> Default for NSDictionaryBuilder<'a, Key, Value> {
+    fn default() -> Self {
+        Self { keys: Vec::new(), values: Vec::new() }
+    }
+}
+impl<'a, Key, Value> Debug for NSDictionaryBuilder<'a, Key, Value> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NSDictionaryBuilder").field("len", &self.keys.len()).finish()
+    }
+}
+impl<'a, Key: NSCopying + 'static, Value: ObjcInstance + 'static> NSDictionaryBuilder<'a, Key, Value> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    ///Adds an entry.
+    pub fn insert(mut self, key: &'a Key, value: &'a Value) -> Self {
+        self.keys.push(key);
+        self.values.push(value);
+        self
+    }
+    pub fn build(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSDictionary<Key, Value>> {
+        //initWithObjects:forKeys:count: doesn't specify which duplicate wins, so go through setObject:forKey:
+        NSDictionary::withDictionary(self.build_mut(pool).as_immutable(), pool)
+    }
+    pub fn build_mut(&self, pool: &ActiveAutoreleasePool) -> StrongMutCell<NSMutableDictionary<Key, Value>> {
+        let mut dictionary = NSMutableDictionary::withCapacity(self.keys.len() as NSUInteger, pool);
+        for (key, value) in self.keys.iter().zip(self.values.iter()) {
+            dictionary.setObjectForKey(value, key, pool);
+        }
+        dictionary
+    }
+}
+/**
+Attribute dictionaries (such as for `NSAttributedString` or `NSFontDescriptor`) hold values of different classes,
+which are typed as `NSObject`.
+*/
+impl<'a, Key: NSCopying + 'static> NSDictionaryBuilder<'a, Key, NSObject> {
+    ///Adds an entry with any kind of value, upcast to `NSObject`.
+    pub fn insert_object<V: ObjcInstance>(self, key: &'a Key, value: &'a V) -> Self {
+        //safety: every ObjcInstance is an object, and dictionaries only require values to be objects
+        let value: &'a NSObject = unsafe { &*(value as *const V as *const NSObject) };
+        self.insert(key, value)
+    }
+}
+impl<'a, Key, Value> Extend<(&'a Key, &'a Value)> for NSDictionaryBuilder<'a, Key, Value> {
+    fn extend<T: IntoIterator<Item=(&'a Key, &'a Value)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.keys.push(key);
+            self.values.push(value);
+        }
+    }
+}
+impl<'a, Key, Value> FromIterator<(&'a Key, &'a Value)> for NSDictionaryBuilder<'a, Key, Value> {
+    fn from_iter<T: IntoIterator<Item=(&'a Key, &'a Value)>>(iter: T) -> Self {
+        let mut builder = Self::default();
+        builder.extend(iter);
+        builder
+    }
 }
 
 
@@ -57,4 +436,147 @@ impl<Key: NSCopying + 'static + Debug,Value: ObjcInstance + 'static> NSDictionar
         let nil_for_key = n.objectForKey(objc_nsstring!("Not a key"), pool);
         assert!(nil_for_key.is_none());
     })
-}
\ No newline at end of file
+}
+
+#[test] fn enumeration() {
+    autoreleasepool(|pool| {
+        let keys = [objc_nsstring!("a"), objc_nsstring!("b"), objc_nsstring!("c")];
+        let values = [objc_nsstring!("3"), objc_nsstring!("1"), objc_nsstring!("2")];
+        let dictionary = NSDictionary::withObjectsForKeys(&values, &keys, pool);
+        assert_eq!(dictionary.count(pool), 3);
+        assert_eq!(dictionary.allKeys(pool).count(pool), 3);
+        assert_eq!(dictionary.allValues(pool).count(pool), 3);
+
+        let mut iterated: Vec<&str> = dictionary.iter(pool).map(|k| k.to_str(pool)).collect();
+        iterated.sort();
+        assert_eq!(iterated, ["a", "b", "c"]);
+        assert_eq!(dictionary.iter(pool).len(), 3);
+        assert_eq!(dictionary.with_pool(pool).into_iter().count(), 3);
+
+        let mut pairs = Vec::new();
+        dictionary.enumerateKeysAndObjectsUsingBlock(|k, v, _| pairs.push((k.to_str(pool).to_owned(), v.to_str(pool).to_owned())), pool);
+        pairs.sort();
+        assert_eq!(pairs, [("a".to_owned(), "3".to_owned()), ("b".to_owned(), "1".to_owned()), ("c".to_owned(), "2".to_owned())]);
+
+        let mut visited = 0;
+        dictionary.enumerateKeysAndObjectsUsingBlock(|_, _, stop| {
+            visited += 1;
+            *stop = true;
+        }, pool);
+        assert_eq!(visited, 1);
+
+        let sorted = dictionary.keysSortedByValueUsingComparator(|a, b| a.to_str(pool).cmp(b.to_str(pool)), pool);
+        let sorted: Vec<&str> = sorted.iter(pool).map(|k| k.to_str(pool)).collect();
+        assert_eq!(sorted, ["b", "c", "a"]);
+    })
+}
+
+#[test] fn conversion() {
+    let map = autoreleasepool(|pool| {
+        let dictionary = NSDictionary::withObjectsForKeys(&[objc_nsstring!("1"), objc_nsstring!("2")], &[objc_nsstring!("a"), objc_nsstring!("b")], pool);
+        dictionary.to_btree_map(pool).unwrap()
+    });
+    assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "b"]);
+    autoreleasepool(|pool| {
+        let dictionary = NSDictionary::<NSString, NSString>::from_btree_map(&map, pool);
+        assert_eq!(dictionary.objectForKey(objc_nsstring!("b"), pool).unwrap().to_str(pool), "2");
+
+        let surrogate = NSString::from_utf16(&[0x61, 0xD800], pool);
+        let dictionary = NSDictionary::withObjectsForKeys(&[objc_nsstring!("1")], &[&*surrogate], pool);
+        assert!(dictionary.to_btree_map(pool).is_err());
+        assert_eq!(dictionary.to_hash_map(pool).len(), 1);
+    });
+
+    let map = autoreleasepool(|pool| {
+        let dictionary = NSDictionary::withObjectsForKeys(&[objc_nsstring!("1"), objc_nsstring!("2")], &[objc_nsstring!("a"), objc_nsstring!("b")], pool);
+        dictionary.to_hash_map(pool)
+    });
+    assert_eq!(map.len(), 2);
+    autoreleasepool(|pool| {
+        //a different object with the same contents finds the entry
+        let a = NSDictionaryKey::new(&*NSString::with_str_copy("a", pool));
+        assert_eq!(map[&a].to_str(pool), "1");
+        let dictionary = NSDictionary::<NSString, NSString>::from_hash_map(&map, pool);
+        assert_eq!(dictionary.count(pool), 2);
+        assert_eq!(dictionary.objectForKey(objc_nsstring!("a"), pool).unwrap().to_str(pool), "1");
+        assert_eq!(dictionary.objectForKey(objc_nsstring!("b"), pool).unwrap().to_str(pool), "2");
+    })
+}
+
+#[cfg(feature="nsvalue")]
+#[test] fn number_keys() {
+    use crate::NSNumber;
+    let map = autoreleasepool(|pool| {
+        let dictionary = NSDictionary::withObjectsForKeys(&[objc_nsstring!("one"), objc_nsstring!("two")], &[&*NSNumber::with_integer(1, pool), &*NSNumber::with_integer(2, pool)], pool);
+        dictionary.to_hash_map(pool)
+    });
+    autoreleasepool(|pool| {
+        //NSNumber's isEqual: compares values across types
+        assert_eq!(map[&NSDictionaryKey::new(&*NSNumber::with_double(2.0, pool))].to_str(pool), "two");
+        let dictionary = NSDictionary::<NSNumber, NSString>::from_hash_map(&map, pool);
+        assert_eq!(dictionary.objectForKey(&NSNumber::with_integer(1, pool), pool).unwrap().to_str(pool), "one");
+    })
+}
+
+#[test] fn mutable() {
+    autoreleasepool(|pool| {
+        let mut dictionary = NSMutableDictionary::<NSString, NSString>::new(pool);
+        dictionary.setObjectForKey(objc_nsstring!("1"), objc_nsstring!("a"), pool);
+        dictionary.setObjectForKey(objc_nsstring!("2"), objc_nsstring!("b"), pool);
+        dictionary.setObjectForKey(objc_nsstring!("3"), objc_nsstring!("a"), pool);
+        assert_eq!(dictionary.count(pool), 2);
+        assert_eq!(dictionary.objectForKey(objc_nsstring!("a"), pool).unwrap().to_str(pool), "3");
+
+        dictionary.removeObjectForKey(objc_nsstring!("a"), pool);
+        dictionary.removeObjectForKey(objc_nsstring!("not a key"), pool);
+        assert_eq!(dictionary.count(pool), 1);
+        assert_eq!(dictionary.with_pool(pool).into_iter().count(), 1);
+
+        let other = NSDictionary::withObjectsForKeys(&[objc_nsstring!("4"), objc_nsstring!("5")], &[objc_nsstring!("b"), objc_nsstring!("c")], pool);
+        dictionary.addEntriesFromDictionary(&other, pool);
+        assert_eq!(dictionary.as_immutable().to_btree_map(pool).unwrap().into_iter().map(|(k, v)| (k, v.to_str(pool).to_owned())).collect::<Vec<_>>(),
+                   [("b".to_owned(), "4".to_owned()), ("c".to_owned(), "5".to_owned())]);
+
+        dictionary.removeAllObjects(pool);
+        assert_eq!(dictionary.count(pool), 0);
+    })
+}
+
+#[test] fn builder() {
+    autoreleasepool(|pool| {
+        let builder: NSDictionaryBuilder<NSString, NSString> = [(objc_nsstring!("a"), objc_nsstring!("1")), (objc_nsstring!("b"), objc_nsstring!("2"))].into_iter().collect();
+        let builder = builder.insert(objc_nsstring!("a"), objc_nsstring!("3"));
+        let dictionary = builder.build(pool);
+        assert_eq!(dictionary.count(pool), 2);
+        assert_eq!(dictionary.objectForKey(objc_nsstring!("a"), pool).unwrap().to_str(pool), "3");
+
+        let mut mutable = builder.build_mut(pool);
+        mutable.removeObjectForKey(objc_nsstring!("b"), pool);
+        assert_eq!(mutable.count(pool), 1);
+        assert_eq!(mutable.objectForKey(objc_nsstring!("a"), pool).unwrap().to_str(pool), "3");
+    })
+}
+
+#[test] fn fallible() {
+    autoreleasepool(|pool| {
+        let error = NSDictionary::try_withObjectsForKeys(&[objc_nsstring!("1")], &[objc_nsstring!("a"), objc_nsstring!("b")], pool).err().unwrap();
+        assert_eq!(error, NSDictionaryError::LengthMismatch { objects: 1, keys: 2 });
+        assert_eq!(error.to_string(), "1 objects but 2 keys");
+
+        let dictionary = NSDictionary::from_pairs(&[(objc_nsstring!("a"), objc_nsstring!("1")), (objc_nsstring!("b"), objc_nsstring!("2"))], pool);
+        assert_eq!(dictionary.count(pool), 2);
+    })
+}
+
+#[test] fn heterogeneous() {
+    autoreleasepool(|pool| {
+        let array = crate::NSArray::with_slice(&[objc_nsstring!("element")], pool);
+        let dictionary = NSDictionaryBuilder::<NSString, NSObject>::new()
+            .insert_object(objc_nsstring!("string"), objc_nsstring!("value"))
+            .insert_object(objc_nsstring!("array"), &*array)
+            .build(pool);
+        assert_eq!(dictionary.count(pool), 2);
+        let value = dictionary.objectForKey(objc_nsstring!("string"), pool).unwrap();
+        assert_eq!(value.description(pool).to_str(pool), "value");
+    })
+}
@@ -1,7 +1,7 @@
 use std::convert::TryInto;
 use std::marker::PhantomData;
-use std::os::raw::{c_ulong, c_void, c_long};
-use objr::bindings::{ActiveAutoreleasePool, Arguable};
+use std::os::raw::{c_char, c_ulong, c_void, c_long};
+use objr::bindings::*;
 use crate::NSUInteger;
 #[derive(Debug)]
 #[repr(C)]
@@ -22,8 +22,65 @@ reference type.
 pub trait NSFastEnumeration {
     type Element;
     fn countByEnumeratingWithStateObjectsCount(self, state: &mut NSFastEnumerationState, objects: *mut *const Self::Element, count: NSUInteger, pool: &ActiveAutoreleasePool)-> NSUInteger;
+    ///The total number of elements, if the collection knows it up front.
+    ///
+    /// [FastEnumerator] uses this for `size_hint`.  The default returns `None`.
+    fn count_hint(self, _pool: &ActiveAutoreleasePool) -> Option<NSUInteger> where Self: Sized {
+        None
+    }
+}
+
+/**
+Pairs a reference with an autorelease pool, so that it can be used where no pool argument is possible,
+such as `for element in array.with_pool(pool)`.
+*/
+pub struct WithPool<'a, T: ?Sized> {
+    value: &'a T,
+    pool: &'a ActiveAutoreleasePool,
+}
+impl<'a, T: ?Sized> WithPool<'a, T> {
+    pub fn new(value: &'a T, pool: &'a ActiveAutoreleasePool) -> Self {
+        Self { value, pool }
+    }
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+    pub fn pool(&self) -> &'a ActiveAutoreleasePool {
+        self.pool
+    }
+}
+impl<'a, T: ?Sized> Clone for WithPool<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
+impl<'a, T: ?Sized> Copy for WithPool<'a, T> {}
 
+///Errors detected while fast-enumerating.  See [FastEnumerator::try_next].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnumerationError {
+    ///The collection was mutated during enumeration.  The enumerator can be restarted.
+    CollectionMutated,
+    ///The [NSFastEnumeration] implementation broke the protocol, for example by returning items without a
+    /// `mutations_ptr`.
+    InvalidEnumeration(&'static str),
+}
+impl std::fmt::Display for EnumerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnumerationError::CollectionMutated => write!(f, "Mutation! The collection was mutated while being enumerated"),
+            EnumerationError::InvalidEnumeration(reason) => write!(f, "Invalid NSFastEnumeration implementation: {}", reason),
+        }
+    }
+}
+impl std::error::Error for EnumerationError {}
+
+/**
+Enumerates an [NSFastEnumeration] collection.
+
+As an [Iterator] this panics if the collection is mutated during enumeration, which matches the exception
+Foundation raises.  To handle that case instead, use [Self::try_next] or [Self::checked].
+*/
 pub struct FastEnumerator<'e, Enumerated> {
     enumerated: Enumerated ,
     state: NSFastEnumerationState,
@@ -34,7 +91,13 @@ pub struct FastEnumerator<'e, Enumerated> {
     //in practice, this seems to be the approach taken by NSArray in many cases.
     stack_head: usize,
     stack_tail: usize,
+    //whether we have seen a batch, and so mutations_ptr_value is meaningful
+    started: bool,
+    //whether the collection has returned 0, which is the only end-of-enumeration signal in the protocol
+    finished: bool,
+    error: Option<EnumerationError>,
     mutations_ptr_value: c_long,
+    yielded: usize,
     dummy: PhantomData<&'e ()>,
     pool: &'e ActiveAutoreleasePool,
 }
@@ -49,35 +112,168 @@ impl<'e, Enumerated> FastEnumerator<'e, Enumerated> {
                 extra: [0,0,0,0,0],
             },
             stack_buf: [std::ptr::null(); 16],
-            stack_head: usize::MAX,
-            stack_tail: usize::MAX,
+            stack_head: 0,
+            stack_tail: 0,
+            started: false,
+            finished: false,
+            error: None,
             mutations_ptr_value: 0,
+            yielded: 0,
             dummy: PhantomData,
             pool,
         }
     }
-}
+    ///Starts over from the beginning of the collection, clearing any error.
+    pub fn restart(&mut self) {
+        self.state = NSFastEnumerationState {
+            state: 0,
+            items_ptr: std::ptr::null_mut(),
+            mutations_ptr: std::ptr::null_mut(),
+            extra: [0,0,0,0,0],
+        };
+        self.stack_head = 0;
+        self.stack_tail = 0;
+        self.started = false;
+        self.finished = false;
+        self.error = None;
+        self.mutations_ptr_value = 0;
+        self.yielded = 0;
+    }
+    ///Converts into an iterator of `Result`s, which yields an error instead of panicking.
+    pub fn checked(self) -> CheckedFastEnumerator<'e, Enumerated> {
+        CheckedFastEnumerator { inner: self, errored: false }
+    }
 
-enum EnumerationResult<Item> {
-    Item(Item),
-    NeedsSlowPath,
-    Done,
+    fn check_mutations(&self) -> Result<(), EnumerationError> {
+        //safety: checked non-null in try_refill before any items are buffered
+        if unsafe{*self.state.mutations_ptr} != self.mutations_ptr_value {
+            Err(EnumerationError::CollectionMutated)
+        }
+        else {
+            Ok(())
+        }
+    }
+    //errors are sticky until restart
+    fn record<T>(&mut self, result: Result<T, EnumerationError>) -> Result<T, EnumerationError> {
+        if let Err(e) = &result {
+            self.error = Some(e.clone());
+            self.stack_head = 0;
+            self.stack_tail = 0;
+        }
+        result
+    }
 }
 
-impl<'e, Enumerated: NSFastEnumeration> FastEnumerator<'e, Enumerated> {
-    fn fast_path(&mut self) -> EnumerationResult<&'e Enumerated::Element> {
-        if self.stack_head < self.stack_tail {
-            let p = unsafe{* self.state.items_ptr.add(self.stack_head)};
-            //safety: API guarantee
-            self.stack_head += 1;
-            EnumerationResult::Item(unsafe{std::mem::transmute(p)})
+impl<'e, Enumerated: NSFastEnumeration + Copy> FastEnumerator<'e, Enumerated> {
+    ///Asks the collection for the next batch.
+    fn try_refill(&mut self) -> Result<(), EnumerationError> {
+        let typecast = self.stack_buf.as_mut_ptr() as *mut *const _ as *mut *const _;
+        let count: usize = self.enumerated.countByEnumeratingWithStateObjectsCount(&mut self.state, typecast, self.stack_buf.len() as u64,self.pool ).try_into().unwrap();
+        self.stack_head = 0;
+        self.stack_tail = 0;
+        if count == 0 {
+            //I have observed mutations_ptr to be null in cases where the array is empty.
+            //for that reason, we need to check for null.
+            if self.started && !self.state.mutations_ptr.is_null() {
+                self.check_mutations()?;
+            }
+            self.finished = true;
+            return Ok(())
+        }
+        if self.state.items_ptr.is_null() {
+            return Err(EnumerationError::InvalidEnumeration("items_ptr is null"))
         }
-        else if self.stack_tail  < self.stack_buf.len()  {
-            EnumerationResult::Done
+        if self.state.mutations_ptr.is_null() {
+            return Err(EnumerationError::InvalidEnumeration("mutations_ptr is null"))
+        }
+        if self.started {
+            self.check_mutations()?;
         }
         else {
-            EnumerationResult::NeedsSlowPath
+            //safety: API guarantee
+            self.mutations_ptr_value = unsafe{*self.state.mutations_ptr};
+            self.started = true;
         }
+        self.stack_tail = count;
+        Ok(())
+    }
+
+    fn try_next_inner(&mut self) -> Result<Option<&'e Enumerated::Element>, EnumerationError> {
+        loop {
+            if self.stack_head < self.stack_tail {
+                //like the compiler does for for-in, check before touching each item
+                self.check_mutations()?;
+                //safety: API guarantee
+                let p = unsafe{* self.state.items_ptr.add(self.stack_head)};
+                self.stack_head += 1;
+                self.yielded += 1;
+                return Ok(Some(unsafe{&*(p as *const Enumerated::Element)}))
+            }
+            if self.finished {
+                return Ok(None)
+            }
+            self.try_refill()?;
+        }
+    }
+
+    /**
+    Like [Iterator::next], but returns an error if the collection was mutated or the enumeration is otherwise invalid.
+
+    Once this returns an error it keeps returning it until [Self::restart].
+    */
+    pub fn try_next(&mut self) -> Result<Option<&'e Enumerated::Element>, EnumerationError> {
+        if let Some(e) = &self.error {
+            return Err(e.clone())
+        }
+        let result = self.try_next_inner();
+        self.record(result)
+    }
+
+    fn try_next_batch_bounds(&mut self) -> Result<Option<(usize, usize)>, EnumerationError> {
+        while self.stack_head >= self.stack_tail {
+            if self.finished {
+                return Ok(None)
+            }
+            self.try_refill()?;
+        }
+        self.check_mutations()?;
+        let start = self.stack_head;
+        let len = self.stack_tail - start;
+        self.stack_head = self.stack_tail;
+        self.yielded += len;
+        Ok(Some((start, len)))
+    }
+
+    ///Like [Self::next_batch], but returns an error instead of panicking.  See [Self::try_next].
+    pub fn try_next_batch(&mut self) -> Result<Option<&[&'e Enumerated::Element]>, EnumerationError> {
+        if let Some(e) = &self.error {
+            return Err(e.clone())
+        }
+        let result = self.try_next_batch_bounds();
+        match self.record(result)? {
+            None => Ok(None),
+            //safety: API guarantee; items_ptr holds stack_tail valid object pointers until the next refill
+            Some((start, len)) => unsafe {
+                let items = self.state.items_ptr.add(start) as *const &'e Enumerated::Element;
+                Ok(Some(std::slice::from_raw_parts(items, len)))
+            }
+        }
+    }
+
+    /**
+    Returns the rest of the current batch as a slice, fetching a new batch if needed.
+
+    This is the zero-copy way to process many elements: the slice points straight at `state.items_ptr`, which
+    is either the collection's internal storage or this enumerator's stack buffer.  For that reason the slice
+    borrows the enumerator.
+
+    Batches are sized by the collection.  This can be mixed with [Iterator::next]; elements are never yielded twice.
+
+    # Panics
+    If the collection is mutated.  See [Self::try_next_batch].
+    */
+    pub fn next_batch(&mut self) -> Option<&[&'e Enumerated::Element]> {
+        self.try_next_batch().unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -85,32 +281,427 @@ impl<'e, Enumerated: NSFastEnumeration + Copy> Iterator for FastEnumerator<'e, E
     type Item = &'e Enumerated::Element;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.fast_path() {
-            EnumerationResult::Item(i) => Some(i),
-            EnumerationResult::Done => None,
-            EnumerationResult::NeedsSlowPath => {
-                let typecast = self.stack_buf.as_mut_ptr() as *mut *const _ as *mut *const _;
-                self.stack_tail = self.enumerated.countByEnumeratingWithStateObjectsCount(&mut self.state, typecast, self.stack_buf.len() as u64,self.pool ).try_into().unwrap();
-                //safety: API guarantee
-                if self.stack_head != usize::MAX && self.mutations_ptr_value != unsafe{*self.state.mutations_ptr} {
-                    panic!("Mutation!")
-                }
-                self.stack_head = 0;
+        self.try_next().unwrap_or_else(|e| panic!("{}", e))
+    }
 
-                //safety: API guarantee
-                //however, I have observed this to be null in cases where the array is empty.
-                //for that reason, we need to check for null.
-                if !self.state.mutations_ptr.is_null() {
-                    self.mutations_ptr_value = unsafe{*self.state.mutations_ptr};
-                }
-                match self.fast_path() {
-                    EnumerationResult::Item(i) => {
-                        Option::Some(i)
-                    },
-                    EnumerationResult::Done => None,
-                    EnumerationResult::NeedsSlowPath => panic!(),
-                }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.enumerated.count_hint(self.pool) {
+            Some(count) => {
+                let remaining = (count as usize).saturating_sub(self.yielded);
+                (remaining, Some(remaining))
+            }
+            None => {
+                //we know at least the buffered items remain
+                (self.stack_tail - self.stack_head, None)
             }
         }
     }
-}
\ No newline at end of file
+}
+
+///An iterator of `Result`s over a [FastEnumerator].  See [FastEnumerator::checked].
+///
+/// After yielding an error, this yields `None` until [Self::restart].
+pub struct CheckedFastEnumerator<'e, Enumerated> {
+    inner: FastEnumerator<'e, Enumerated>,
+    errored: bool,
+}
+impl<'e, Enumerated> CheckedFastEnumerator<'e, Enumerated> {
+    ///Starts over from the beginning of the collection.
+    pub fn restart(&mut self) {
+        self.inner.restart();
+        self.errored = false;
+    }
+    pub fn into_inner(self) -> FastEnumerator<'e, Enumerated> {
+        self.inner
+    }
+}
+impl<'e, Enumerated: NSFastEnumeration + Copy> Iterator for CheckedFastEnumerator<'e, Enumerated> where Enumerated::Element: 'e {
+    type Item = Result<&'e Enumerated::Element, EnumerationError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None
+        }
+        match self.inner.try_next() {
+            Ok(Some(element)) => Some(Ok(element)),
+            Ok(None) => None,
+            Err(e) => {
+                self.errored = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+objc_class! {
+    pub struct NSEnumeratorRaw {
+        @class(NSEnumerator)
+    }
+}
+objc_class_newtype! {
+    pub struct NSEnumerator<Element>: NSEnumeratorRaw;
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("countByEnumeratingWithState:objects:count:")
+    }
+    impl Selectors for Sel {}
+}
+impl<Element: ObjcInstance + 'static> NSEnumerator<Element> {
+    pub fn iter<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> FastEnumerator<&Self> {
+        FastEnumerator::new(self, pool)
+    }
+    pub fn with_pool<'a>(&'a self, pool: &'a ActiveAutoreleasePool) -> WithPool<'a, Self> {
+        WithPool::new(self, pool)
+    }
+}
+impl<Element: Arguable + 'static> NSFastEnumeration for &NSEnumerator<Element> {
+    type Element = Element;
+    fn countByEnumeratingWithStateObjectsCount(self, state: &mut NSFastEnumerationState, objects: *mut *const Self::Element, count: NSUInteger, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        unsafe {
+            //the equivalent of assume_nonmut_perform for this argument
+            let transmut: *mut *mut Element = std::mem::transmute(objects);
+            NSEnumerator::perform_primitive(self.assume_nonmut_perform(), Sel::countByEnumeratingWithState_objects_count(), pool, (state,transmut, count))
+        }
+    }
+}
+impl<'a, Element: Arguable + ObjcInstance + 'static> IntoIterator for WithPool<'a, NSEnumerator<Element>> {
+    type Item = &'a Element;
+    type IntoIter = FastEnumerator<'a, &'a NSEnumerator<Element>>;
+    fn into_iter(self) -> Self::IntoIter {
+        FastEnumerator::new(self.value, self.pool)
+    }
+}
+
+//Rust-side collections never mutate while borrowed, so they all share one counter that is never written.
+static IMMUTABLE_MUTATIONS: c_long = 0;
+
+///Serves `items` in a single batch from its own storage.
+fn enumerate_slice(items: &[*const c_void], state: &mut NSFastEnumerationState) -> NSUInteger {
+    if state.state != 0 {
+        return 0
+    }
+    state.state = 1;
+    state.items_ptr = items.as_ptr() as *mut *const c_void;
+    state.mutations_ptr = &IMMUTABLE_MUTATIONS as *const c_long as *mut c_long;
+    items.len() as NSUInteger
+}
+
+/**
+Rust slices of ObjC objects can be fast-enumerated directly.
+
+```ignore
+let strings = [objc_nsstring!("a"), objc_nsstring!("b")];
+for string in FastEnumerator::new(&strings[..], pool) { }
+```
+
+To hand a slice to ObjC code that accepts `id<NSFastEnumeration>`, see [fast_enumeration_object].
+*/
+impl<'a, Element> NSFastEnumeration for &'a [&'a Element] {
+    type Element = Element;
+    fn countByEnumeratingWithStateObjectsCount(self, state: &mut NSFastEnumerationState, _objects: *mut *const Element, _count: NSUInteger, _pool: &ActiveAutoreleasePool) -> NSUInteger {
+        //safety: &Element has the same layout as a pointer
+        let items: &[*const c_void] = unsafe { std::slice::from_raw_parts(self.as_ptr() as *const *const c_void, self.len()) };
+        enumerate_slice(items, state)
+    }
+    fn count_hint(self, _pool: &ActiveAutoreleasePool) -> Option<NSUInteger> {
+        Some(self.len() as NSUInteger)
+    }
+}
+impl<'e, 'a, Element> ExactSizeIterator for FastEnumerator<'e, &'a [&'a Element]> where Element: 'e {}
+
+//objr binds messaging but not class definition, so the few runtime calls needed to define a class are declared here
+#[allow(non_camel_case_types)]
+type id = *mut c_void;
+extern "C" {
+    fn objc_getClass(name: *const c_char) -> id;
+    fn objc_allocateClassPair(superclass: id, name: *const c_char, extra_bytes: usize) -> id;
+    fn objc_registerClassPair(cls: id);
+    fn objc_getProtocol(name: *const c_char) -> id;
+    fn class_addProtocol(cls: id, protocol: id) -> bool;
+    fn class_addMethod(cls: id, name: Sel, imp: *const c_void, types: *const c_char) -> bool;
+    fn class_createInstance(cls: id, extra_bytes: usize) -> id;
+    fn object_getIndexedIvars(obj: id) -> *mut c_void;
+}
+
+//stored in the object's indexed ivars
+#[repr(C)]
+struct SliceIvars {
+    ptr: *const *const c_void,
+    len: usize,
+}
+
+extern "C" fn slice_count_by_enumerating(this: id, _cmd: Sel, state: *mut NSFastEnumerationState, _objects: *mut *const c_void, _count: NSUInteger) -> NSUInteger {
+    unsafe {
+        let ivars = &*(object_getIndexedIvars(this) as *const SliceIvars);
+        let items = std::slice::from_raw_parts(ivars.ptr, ivars.len);
+        enumerate_slice(items, &mut *state)
+    }
+}
+
+/**
+Defines the slice enumeration class under `name`, or returns the existing class if `name` is already registered.
+
+The name includes the crate version, so an existing class comes from another copy of this same version linked into the
+process, and has the same ivar layout and method.
+*/
+unsafe fn register_slice_enumeration_class(name: &std::ffi::CStr) -> id {
+    let superclass = NSObject::class() as *const Class<NSObject> as id;
+    let cls = objc_allocateClassPair(superclass, name.as_ptr(), 0);
+    if cls.is_null() {
+        let existing = objc_getClass(name.as_ptr());
+        assert!(!existing.is_null(), "can't define or find ObjC class {:?}", name);
+        return existing
+    }
+    let added = class_addMethod(cls, Sel::countByEnumeratingWithState_objects_count(), slice_count_by_enumerating as *const c_void, b"Q@:^v^@Q\0".as_ptr() as *const c_char);
+    assert!(added);
+    let protocol = objc_getProtocol(b"NSFastEnumeration\0".as_ptr() as *const c_char);
+    if !protocol.is_null() {
+        class_addProtocol(cls, protocol);
+    }
+    objc_registerClassPair(cls);
+    cls
+}
+
+fn slice_enumeration_class() -> id {
+    static CLASS: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
+    *CLASS.get_or_init(|| unsafe {
+        let name = concat!("FoundationrSliceEnumeration_", env!("CARGO_PKG_VERSION"), "\0");
+        register_slice_enumeration_class(std::ffi::CStr::from_bytes_with_nul(name.as_bytes()).unwrap()) as usize
+    }) as id
+}
+
+/**
+Creates an ObjC object conforming to `NSFastEnumeration` that enumerates `items`, without building an `NSArray`.
+
+Like [crate::NSStringExtension::from_borrowed_str], the object borrows its argument (and does not retain the elements).
+ObjC APIs that only enumerate their argument during the call are fine; you must check that the API doesn't keep the
+object past the lifetime.
+*/
+pub fn fast_enumeration_object<'a, Element: ObjcInstance>(items: &'a [&'a Element]) -> StrongLifetimeCell<'a, NSObject> {
+    unsafe {
+        let object = class_createInstance(slice_enumeration_class(), std::mem::size_of::<SliceIvars>());
+        let ivars = object_getIndexedIvars(object) as *mut SliceIvars;
+        ivars.write(SliceIvars { ptr: items.as_ptr() as *const *const c_void, len: items.len() });
+        //class_createInstance returns +1, like alloc
+        NSObject::assume_nonnil(object as *const NSObject).assume_retained_limited()
+    }
+}
+
+/**
+An ObjC object that is assumed to conform to `NSFastEnumeration`, yielding `Element`s.
+
+This lets you fast-enumerate objects whose static type doesn't say so, such as the result of [fast_enumeration_object].
+*/
+pub struct NSFastEnumerationObject<'a, Element> {
+    object: &'a NSObject,
+    element: PhantomData<&'a Element>,
+}
+impl<'a, Element> Clone for NSFastEnumerationObject<'a, Element> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<'a, Element> Copy for NSFastEnumerationObject<'a, Element> {}
+impl<'a, Element> NSFastEnumerationObject<'a, Element> {
+    ///# Safety
+    /// `object` must respond to `countByEnumeratingWithState:objects:count:` and yield only `Element`s.
+    pub unsafe fn assume(object: &'a NSObject) -> Self {
+        Self { object, element: PhantomData }
+    }
+}
+impl<'a, Element: Arguable + 'static> NSFastEnumeration for NSFastEnumerationObject<'a, Element> {
+    type Element = Element;
+    fn countByEnumeratingWithStateObjectsCount(self, state: &mut NSFastEnumerationState, objects: *mut *const Element, count: NSUInteger, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        unsafe {
+            //the equivalent of assume_nonmut_perform for this argument
+            let transmut: *mut *mut Element = std::mem::transmute(objects);
+            NSObject::perform_primitive(self.object.assume_nonmut_perform(), Sel::countByEnumeratingWithState_objects_count(), pool, (state, transmut, count))
+        }
+    }
+}
+
+#[cfg(test)]
+struct MockCollection {
+    items: Vec<u32>,
+    ptrs: Vec<*const u32>,
+    //maximum items returned per call
+    batch: usize,
+    //return items in the caller's buffer instead of our own storage
+    use_stack_buf: bool,
+    mutations: std::cell::Cell<c_long>,
+    null_mutations_ptr: bool,
+    null_items_ptr: bool,
+}
+#[cfg(test)]
+impl MockCollection {
+    fn new(len: u32, batch: usize, use_stack_buf: bool) -> Box<Self> {
+        let mut s = Box::new(MockCollection {
+            items: (0..len).collect(),
+            ptrs: Vec::new(),
+            batch,
+            use_stack_buf,
+            mutations: std::cell::Cell::new(0),
+            null_mutations_ptr: false,
+            null_items_ptr: false,
+        });
+        s.ptrs = s.items.iter().map(|i| i as *const u32).collect();
+        s
+    }
+}
+#[cfg(test)]
+impl NSFastEnumeration for &MockCollection {
+    type Element = u32;
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn countByEnumeratingWithStateObjectsCount(self, state: &mut NSFastEnumerationState, objects: *mut *const u32, count: NSUInteger, _pool: &ActiveAutoreleasePool) -> NSUInteger {
+        let start = state.state as usize;
+        let mut n = self.batch.min(self.ptrs.len() - start);
+        if self.use_stack_buf {
+            n = n.min(count as usize);
+            unsafe { std::ptr::copy_nonoverlapping(self.ptrs.as_ptr().add(start), objects, n) };
+            state.items_ptr = objects as *mut *const c_void;
+        }
+        else {
+            state.items_ptr = unsafe { self.ptrs.as_ptr().add(start) } as *mut *const c_void;
+        }
+        if self.null_items_ptr {
+            state.items_ptr = std::ptr::null_mut();
+        }
+        state.mutations_ptr = if self.null_mutations_ptr { std::ptr::null_mut() } else { self.mutations.as_ptr() };
+        state.state = (start + n) as c_ulong;
+        n as NSUInteger
+    }
+    fn count_hint(self, _pool: &ActiveAutoreleasePool) -> Option<NSUInteger> {
+        Some(self.items.len() as NSUInteger)
+    }
+}
+
+#[test] fn mock_enumeration() {
+    autoreleasepool(|pool| {
+        //batches smaller than, equal to, and larger than the stack buffer, in both storage modes
+        for len in [0, 1, 15, 16, 17, 100] {
+            for batch in [1, 7, 16, 40, 1000] {
+                for use_stack_buf in [false, true] {
+                    let mock = MockCollection::new(len, batch, use_stack_buf);
+                    let iter = FastEnumerator::new(&*mock, pool);
+                    assert_eq!(iter.size_hint(), (len as usize, Some(len as usize)));
+                    let items: Vec<u32> = iter.copied().collect();
+                    assert_eq!(items, mock.items, "len {} batch {} stack {}", len, batch, use_stack_buf);
+
+                    let mut iter = FastEnumerator::new(&*mock, pool);
+                    let mut batched: Vec<u32> = Vec::new();
+                    while let Some(b) = iter.try_next_batch().unwrap() {
+                        batched.extend(b.iter().copied());
+                    }
+                    assert_eq!(batched, mock.items);
+                }
+            }
+        }
+    })
+}
+
+#[test] fn mock_mutation_is_recoverable() {
+    autoreleasepool(|pool| {
+        let mock = MockCollection::new(40, 16, false);
+        let mut iter = FastEnumerator::new(&*mock, pool);
+        assert_eq!(iter.try_next(), Ok(Some(&0)));
+        mock.mutations.set(1);
+        //detected before the next item, not only at the next batch
+        assert_eq!(iter.try_next(), Err(EnumerationError::CollectionMutated));
+        //sticky until restart
+        assert_eq!(iter.try_next(), Err(EnumerationError::CollectionMutated));
+        assert_eq!(iter.try_next_batch(), Err(EnumerationError::CollectionMutated));
+        iter.restart();
+        let items: Vec<u32> = iter.copied().collect();
+        assert_eq!(items, mock.items);
+
+        //checked iterator yields the error once, then stops
+        let mut checked = FastEnumerator::new(&*mock, pool).checked();
+        assert_eq!(checked.next(), Some(Ok(&0)));
+        mock.mutations.set(2);
+        assert_eq!(checked.next(), Some(Err(EnumerationError::CollectionMutated)));
+        assert_eq!(checked.next(), None);
+        checked.restart();
+        assert_eq!(checked.collect::<Result<Vec<_>, _>>().unwrap().len(), 40);
+    })
+}
+
+#[test] #[should_panic(expected = "Mutation!")] fn mock_mutation_panics() {
+    autoreleasepool(|pool| {
+        let mock = MockCollection::new(40, 16, true);
+        let mut iter = FastEnumerator::new(&*mock, pool);
+        iter.next();
+        mock.mutations.set(1);
+        for _ in iter {}
+    })
+}
+
+#[test] fn mock_invalid_enumeration() {
+    autoreleasepool(|pool| {
+        let mut mock = MockCollection::new(3, 16, false);
+        mock.null_mutations_ptr = true;
+        let mut iter = FastEnumerator::new(&*mock, pool);
+        assert_eq!(iter.try_next(), Err(EnumerationError::InvalidEnumeration("mutations_ptr is null")));
+
+        let mut mock = MockCollection::new(3, 16, false);
+        mock.null_items_ptr = true;
+        let mut checked = FastEnumerator::new(&*mock, pool).checked();
+        assert_eq!(checked.next(), Some(Err(EnumerationError::InvalidEnumeration("items_ptr is null"))));
+        assert_eq!(checked.next(), None);
+
+        //empty collections may leave mutations_ptr null
+        let mut mock = MockCollection::new(0, 16, false);
+        mock.null_mutations_ptr = true;
+        assert_eq!(FastEnumerator::new(&*mock, pool).try_next(), Ok(None));
+    })
+}
+
+#[test] fn slice_provider() {
+    autoreleasepool(|pool| {
+        let items: Vec<u32> = (0..50).collect();
+        let refs: Vec<&u32> = items.iter().collect();
+        let iter = FastEnumerator::new(&refs[..], pool);
+        assert_eq!(iter.len(), 50);
+        assert_eq!(iter.copied().collect::<Vec<_>>(), items);
+
+        let mut iter = FastEnumerator::new(&refs[..], pool);
+        assert_eq!(iter.try_next_batch().unwrap().unwrap().len(), 50);
+        assert_eq!(iter.try_next_batch(), Ok(None));
+
+        let empty: [&u32; 0] = [];
+        assert_eq!(FastEnumerator::new(&empty[..], pool).try_next(), Ok(None));
+    })
+}
+
+#[test] fn slice_object() {
+    autoreleasepool(|pool| {
+        let strings = [objc_nsstring!("a"), objc_nsstring!("b"), objc_nsstring!("c")];
+        let object = fast_enumeration_object(&strings[..]);
+        let enumeration = unsafe { NSFastEnumerationObject::<NSString>::assume(&object) };
+        let collected: Vec<&str> = FastEnumerator::new(enumeration, pool).map(|s| s.to_str(pool)).collect();
+        assert_eq!(collected, ["a", "b", "c"]);
+        //a second enumeration starts over
+        assert_eq!(FastEnumerator::new(enumeration, pool).count(), 3);
+    })
+}
+
+#[test] fn slice_enumeration_class_registration() {
+    let name = std::ffi::CStr::from_bytes_with_nul(b"FoundationrSliceEnumerationTest\0").unwrap();
+    //registering again, as a second copy of the crate would, finds the existing class instead of panicking
+    let first = unsafe { register_slice_enumeration_class(name) };
+    let second = unsafe { register_slice_enumeration_class(name) };
+    assert!(!first.is_null());
+    assert_eq!(first, second);
+    assert_eq!(slice_enumeration_class(), slice_enumeration_class());
+    assert_ne!(slice_enumeration_class(), first);
+
+    autoreleasepool(|pool| {
+        let strings = [objc_nsstring!("x"), objc_nsstring!("y")];
+        unsafe {
+            let object = class_createInstance(second, std::mem::size_of::<SliceIvars>());
+            (object_getIndexedIvars(object) as *mut SliceIvars).write(SliceIvars { ptr: strings.as_ptr() as *const *const c_void, len: strings.len() });
+            let object = NSObject::assume_nonnil(object as *const NSObject).assume_retained_limited();
+            let enumeration = NSFastEnumerationObject::<NSString>::assume(&object);
+            assert_eq!(FastEnumerator::new(enumeration, pool).map(|s| s.to_str(pool)).collect::<Vec<_>>(), ["x", "y"]);
+        }
+    })
+}
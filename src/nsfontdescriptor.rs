@@ -0,0 +1,434 @@
+//! <AppKit/NSFontDescriptor.h>
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+use objr::bindings::*;
+use coregraphicsr::CGFloat;
+use crate::{NSArray, NSCopying, NSDictionary, NSNumber, NSUInteger};
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {}
+
+objc_class_newtype! {
+    pub struct NSFontDescriptorAttributeName: NSString;
+}
+impl NSCopying for NSFontDescriptorAttributeName {}
+
+objc_class_newtype! {
+    pub struct NSFontDescriptorTraitKey: NSString;
+}
+impl NSCopying for NSFontDescriptorTraitKey {}
+
+extern "C" {
+    static NSFontFamilyAttribute: &'static NSFontDescriptorAttributeName;
+    static NSFontNameAttribute: &'static NSFontDescriptorAttributeName;
+    static NSFontFaceAttribute: &'static NSFontDescriptorAttributeName;
+    static NSFontSizeAttribute: &'static NSFontDescriptorAttributeName;
+    static NSFontVisibleNameAttribute: &'static NSFontDescriptorAttributeName;
+    static NSFontTraitsAttribute: &'static NSFontDescriptorAttributeName;
+    static NSFontFixedAdvanceAttribute: &'static NSFontDescriptorAttributeName;
+
+    static NSFontSymbolicTrait: &'static NSFontDescriptorTraitKey;
+    static NSFontWeightTrait: &'static NSFontDescriptorTraitKey;
+    static NSFontWidthTrait: &'static NSFontDescriptorTraitKey;
+    static NSFontSlantTrait: &'static NSFontDescriptorTraitKey;
+}
+
+impl NSFontDescriptorAttributeName {
+    pub fn family() -> &'static Self {
+        unsafe { &NSFontFamilyAttribute }
+    }
+    pub fn name() -> &'static Self {
+        unsafe { &NSFontNameAttribute }
+    }
+    pub fn face() -> &'static Self {
+        unsafe { &NSFontFaceAttribute }
+    }
+    pub fn size() -> &'static Self {
+        unsafe { &NSFontSizeAttribute }
+    }
+    pub fn visible_name() -> &'static Self {
+        unsafe { &NSFontVisibleNameAttribute }
+    }
+    pub fn traits() -> &'static Self {
+        unsafe { &NSFontTraitsAttribute }
+    }
+    pub fn fixed_advance() -> &'static Self {
+        unsafe { &NSFontFixedAdvanceAttribute }
+    }
+}
+
+impl NSFontDescriptorTraitKey {
+    pub fn symbolic() -> &'static Self {
+        unsafe { &NSFontSymbolicTrait }
+    }
+    pub fn weight() -> &'static Self {
+        unsafe { &NSFontWeightTrait }
+    }
+    pub fn width() -> &'static Self {
+        unsafe { &NSFontWidthTrait }
+    }
+    pub fn slant() -> &'static Self {
+        unsafe { &NSFontSlantTrait }
+    }
+}
+
+/**
+The family class stored in the high 4 bits of [NSFontDescriptorSymbolicTraits].
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NSFontFamilyClass {
+    Unknown,
+    OldStyleSerifs,
+    TransitionalSerifs,
+    ModernSerifs,
+    ClarendonSerifs,
+    SlabSerifs,
+    FreeformSerifs,
+    SansSerif,
+    Ornamentals,
+    Scripts,
+    Symbolic,
+    ///A class value Foundation has not defined (yet).
+    Other(UndefinedFamilyClass),
+}
+
+///A 4-bit family class value with no [NSFontFamilyClass] variant: 6, 11, 13, 14 or 15.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UndefinedFamilyClass(u8);
+impl UndefinedFamilyClass {
+    ///`None` if `raw` is a defined class or doesn't fit in 4 bits.
+    pub fn new(raw: u32) -> Option<Self> {
+        match raw {
+            6 | 11 | 13..=15 => Some(UndefinedFamilyClass(raw as u8)),
+            _ => None,
+        }
+    }
+    pub fn raw(self) -> u32 {
+        self.0 as u32
+    }
+}
+
+impl NSFontFamilyClass {
+    fn from_raw(raw: u32) -> Self {
+        match raw {
+            0 => NSFontFamilyClass::Unknown,
+            1 => NSFontFamilyClass::OldStyleSerifs,
+            2 => NSFontFamilyClass::TransitionalSerifs,
+            3 => NSFontFamilyClass::ModernSerifs,
+            4 => NSFontFamilyClass::ClarendonSerifs,
+            5 => NSFontFamilyClass::SlabSerifs,
+            7 => NSFontFamilyClass::FreeformSerifs,
+            8 => NSFontFamilyClass::SansSerif,
+            9 => NSFontFamilyClass::Ornamentals,
+            10 => NSFontFamilyClass::Scripts,
+            12 => NSFontFamilyClass::Symbolic,
+            //raw is 4 bits, so everything else is undefined
+            other => NSFontFamilyClass::Other(UndefinedFamilyClass(other as u8)),
+        }
+    }
+    fn raw(self) -> u32 {
+        match self {
+            NSFontFamilyClass::Unknown => 0,
+            NSFontFamilyClass::OldStyleSerifs => 1,
+            NSFontFamilyClass::TransitionalSerifs => 2,
+            NSFontFamilyClass::ModernSerifs => 3,
+            NSFontFamilyClass::ClarendonSerifs => 4,
+            NSFontFamilyClass::SlabSerifs => 5,
+            NSFontFamilyClass::FreeformSerifs => 7,
+            NSFontFamilyClass::SansSerif => 8,
+            NSFontFamilyClass::Ornamentals => 9,
+            NSFontFamilyClass::Scripts => 10,
+            NSFontFamilyClass::Symbolic => 12,
+            NSFontFamilyClass::Other(other) => other.raw(),
+        }
+    }
+}
+
+/**
+`NSFontDescriptorSymbolicTraits`, a `uint32_t` bitmask.
+
+The low bits are trait flags, the high 4 bits ([Self::CLASS_MASK]) are a [NSFontFamilyClass].
+*/
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSFontDescriptorSymbolicTraits(pub u32);
+
+impl NSFontDescriptorSymbolicTraits {
+    pub const ITALIC: Self = Self(1 << 0);
+    pub const BOLD: Self = Self(1 << 1);
+    pub const EXPANDED: Self = Self(1 << 5);
+    pub const CONDENSED: Self = Self(1 << 6);
+    pub const MONO_SPACE: Self = Self(1 << 10);
+    pub const VERTICAL: Self = Self(1 << 11);
+    pub const UI_OPTIMIZED: Self = Self(1 << 12);
+    pub const TIGHT_LEADING: Self = Self(1 << 15);
+    pub const LOOSE_LEADING: Self = Self(1 << 16);
+    pub const CLASS_MASK: Self = Self(0xF000_0000);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    ///True if all bits in `other` are set in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0
+    }
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0
+    }
+    pub fn family_class(self) -> NSFontFamilyClass {
+        NSFontFamilyClass::from_raw(self.0 >> 28)
+    }
+    ///Replaces the family class bits, keeping the trait flags.
+    pub fn with_family_class(self, class: NSFontFamilyClass) -> Self {
+        Self((self.0 & !Self::CLASS_MASK.0) | (class.raw() << 28))
+    }
+}
+impl BitOr for NSFontDescriptorSymbolicTraits {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+impl BitOrAssign for NSFontDescriptorSymbolicTraits {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0
+    }
+}
+impl BitAnd for NSFontDescriptorSymbolicTraits {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+impl From<u32> for NSFontDescriptorSymbolicTraits {
+    fn from(raw: u32) -> Self {
+        Self(raw)
+    }
+}
+impl From<NSFontDescriptorSymbolicTraits> for u32 {
+    fn from(traits: NSFontDescriptorSymbolicTraits) -> Self {
+        traits.0
+    }
+}
+
+objc_class! {
+    pub struct NSFontDescriptor {
+        @class(NSFontDescriptor)
+    }
+}
+//immutable
+unsafe impl Send for NSFontDescriptor {}
+unsafe impl Sync for NSFontDescriptor {}
+impl NSCopying for NSFontDescriptor {}
+
+objc_class! {
+    pub(crate) struct NSSet {
+        @class(NSSet)
+    }
+}
+
+objc_selector_group! {
+    trait NSFontDescriptorSelectors {
+        @selector("fontDescriptorWithFontAttributes:")
+        @selector("fontDescriptorWithName:size:")
+        @selector("fontAttributes")
+        @selector("objectForKey:")
+        @selector("postscriptName")
+        @selector("pointSize")
+        @selector("symbolicTraits")
+        @selector("fontDescriptorWithSymbolicTraits:")
+        @selector("fontDescriptorWithFamily:")
+        @selector("fontDescriptorWithFace:")
+        @selector("fontDescriptorWithSize:")
+        @selector("fontDescriptorByAddingAttributes:")
+        @selector("matchingFontDescriptorsWithMandatoryKeys:")
+        @selector("matchingFontDescriptorWithMandatoryKeys:")
+        @selector("setWithObjects:count:")
+    }
+    impl NSFontDescriptorSelectors for Sel {}
+}
+
+#[allow(non_snake_case)]
+impl NSFontDescriptor {
+    ///```objc
+    /// + (NSFontDescriptor *)fontDescriptorWithFontAttributes:(nullable NSDictionary<NSFontDescriptorAttributeName, id> *)attributes;
+    /// ```
+    pub fn withFontAttributes(attributes: Option<&NSDictionary<NSFontDescriptorAttributeName, NSObject>>, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::fontDescriptorWithFontAttributes_(), pool, (attributes.as_ptr().assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///```objc
+    /// + (NSFontDescriptor *)fontDescriptorWithName:(NSString *)fontName size:(CGFloat)size;
+    /// ```
+    pub fn withNameSize(name: &NSString, size: CGFloat, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::fontDescriptorWithName_size(), pool, (name.assume_nonmut_perform(), size));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn fontAttributes(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSDictionary<NSFontDescriptorAttributeName, NSObject>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::fontAttributes(), pool, ());
+            NSDictionary::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn objectForKey(&self, key: &NSFontDescriptorAttributeName, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSObject>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::objectForKey_(), pool, (key.assume_nonmut_perform(),));
+            NSObject::nullable(raw).assume_retained()
+        }
+    }
+    pub fn postscriptName(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSString>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::postscriptName(), pool, ());
+            NSString::nullable(raw).assume_retained()
+        }
+    }
+    pub fn pointSize(&self, pool: &ActiveAutoreleasePool) -> CGFloat {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::pointSize(), pool, ())
+        }
+    }
+    pub fn symbolicTraits(&self, pool: &ActiveAutoreleasePool) -> NSFontDescriptorSymbolicTraits {
+        unsafe {
+            let raw: u32 = Self::perform_primitive(self.assume_nonmut_perform(), Sel::symbolicTraits(), pool, ());
+            NSFontDescriptorSymbolicTraits(raw)
+        }
+    }
+    ///The value of [NSFontDescriptorAttributeName::family], if any.
+    pub fn family(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSString>> {
+        self.string_attribute(NSFontDescriptorAttributeName::family(), pool)
+    }
+    ///The value of [NSFontDescriptorAttributeName::face], if any.
+    pub fn face(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSString>> {
+        self.string_attribute(NSFontDescriptorAttributeName::face(), pool)
+    }
+    ///The traits dictionary stored under [NSFontDescriptorAttributeName::traits], if any.
+    pub fn traits(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSDictionary<NSFontDescriptorTraitKey, NSNumber>>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::objectForKey_(), pool, (NSFontDescriptorAttributeName::traits().assume_nonmut_perform(),));
+            NSDictionary::nullable(raw).assume_retained()
+        }
+    }
+    fn string_attribute(&self, key: &NSFontDescriptorAttributeName, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSString>> {
+        unsafe {
+            //these attributes are documented as NSString
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::objectForKey_(), pool, (key.assume_nonmut_perform(),));
+            NSString::nullable(raw).assume_retained()
+        }
+    }
+
+    ///```objc
+    /// - (nullable NSFontDescriptor *)fontDescriptorWithSymbolicTraits:(NSFontDescriptorSymbolicTraits)symbolicTraits;
+    /// ```
+    ///
+    /// Returns `None` if no descriptor can satisfy the traits.
+    pub fn fontDescriptorWithSymbolicTraits(&self, traits: NSFontDescriptorSymbolicTraits, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Self>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::fontDescriptorWithSymbolicTraits_(), pool, (traits.0,));
+            Self::nullable(raw).assume_retained()
+        }
+    }
+    pub fn fontDescriptorWithFamily(&self, family: &NSString, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::fontDescriptorWithFamily_(), pool, (family.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn fontDescriptorWithFace(&self, face: &NSString, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::fontDescriptorWithFace_(), pool, (face.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn fontDescriptorWithSize(&self, size: CGFloat, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::fontDescriptorWithSize_(), pool, (size,));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn fontDescriptorByAddingAttributes(&self, attributes: &NSDictionary<NSFontDescriptorAttributeName, NSObject>, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::fontDescriptorByAddingAttributes_(), pool, (attributes.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+
+    fn mandatory_key_set(keys: &[&NSFontDescriptorAttributeName], pool: &ActiveAutoreleasePool) -> StrongCell<NSSet> {
+        unsafe {
+            //the equivalent of assume_nonmut_perform for this argument
+            let transmut: *mut *mut NSFontDescriptorAttributeName = std::mem::transmute(keys.as_ptr());
+            let raw = Class::perform_autorelease_to_retain(NSSet::class().assume_nonmut_perform(), Sel::setWithObjects_count(), pool, (transmut, keys.len() as NSUInteger));
+            NSSet::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///```objc
+    /// - (NSArray<NSFontDescriptor *> *)matchingFontDescriptorsWithMandatoryKeys:(nullable NSSet<NSFontDescriptorAttributeName> *)mandatoryKeys;
+    /// ```
+    pub fn matchingFontDescriptorsWithMandatoryKeys(&self, mandatory_keys: &[&NSFontDescriptorAttributeName], pool: &ActiveAutoreleasePool) -> StrongCell<NSArray<NSFontDescriptor>> {
+        let set = Self::mandatory_key_set(mandatory_keys, pool);
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::matchingFontDescriptorsWithMandatoryKeys_(), pool, (set.assume_nonmut_perform(),));
+            NSArray::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///```objc
+    /// - (nullable NSFontDescriptor *)matchingFontDescriptorWithMandatoryKeys:(nullable NSSet<NSFontDescriptorAttributeName> *)mandatoryKeys;
+    /// ```
+    pub fn matchingFontDescriptorWithMandatoryKeys(&self, mandatory_keys: &[&NSFontDescriptorAttributeName], pool: &ActiveAutoreleasePool) -> Option<StrongCell<Self>> {
+        let set = Self::mandatory_key_set(mandatory_keys, pool);
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::matchingFontDescriptorWithMandatoryKeys_(), pool, (set.assume_nonmut_perform(),));
+            Self::nullable(raw).assume_retained()
+        }
+    }
+}
+
+#[test] fn symbolic_traits() {
+    let mut traits = NSFontDescriptorSymbolicTraits::BOLD | NSFontDescriptorSymbolicTraits::ITALIC;
+    assert_eq!(traits.bits(), 0b11);
+    assert!(traits.contains(NSFontDescriptorSymbolicTraits::BOLD));
+    assert!(!traits.contains(NSFontDescriptorSymbolicTraits::MONO_SPACE));
+    traits.remove(NSFontDescriptorSymbolicTraits::BOLD);
+    assert_eq!(traits, NSFontDescriptorSymbolicTraits::ITALIC);
+    assert_eq!(traits.family_class(), NSFontFamilyClass::Unknown);
+
+    let traits = traits.with_family_class(NSFontFamilyClass::SansSerif);
+    assert_eq!(u32::from(traits), 0x8000_0001);
+    assert_eq!(traits.family_class(), NSFontFamilyClass::SansSerif);
+    assert!(traits.contains(NSFontDescriptorSymbolicTraits::ITALIC));
+    let undefined = UndefinedFamilyClass::new(6).unwrap();
+    assert_eq!(NSFontDescriptorSymbolicTraits::from(6 << 28).family_class(), NSFontFamilyClass::Other(undefined));
+    assert_eq!(traits.with_family_class(NSFontFamilyClass::Other(undefined)).family_class(), NSFontFamilyClass::Other(undefined));
+    assert_eq!(UndefinedFamilyClass::new(1), None);
+    assert_eq!(UndefinedFamilyClass::new(16), None);
+    for raw in 0..16 {
+        let class = NSFontDescriptorSymbolicTraits::from(raw << 28).family_class();
+        assert_eq!(NSFontDescriptorSymbolicTraits::default().with_family_class(class).bits(), raw << 28);
+    }
+    assert_eq!((traits & NSFontDescriptorSymbolicTraits::CLASS_MASK).bits(), 0x8000_0000);
+}
+
+#[test] fn smoke() {
+    autoreleasepool(|pool| {
+        let descriptor = NSFontDescriptor::withNameSize(objc_nsstring!("Helvetica"), 12.0, pool);
+        assert_eq!(descriptor.pointSize(pool), 12.0);
+        let bold = descriptor.fontDescriptorWithSymbolicTraits(NSFontDescriptorSymbolicTraits::BOLD, pool).unwrap();
+        assert!(bold.symbolicTraits(pool).contains(NSFontDescriptorSymbolicTraits::BOLD));
+        let family = NSFontDescriptor::withFontAttributes(None, pool).fontDescriptorWithFamily(objc_nsstring!("Helvetica"), pool);
+        assert_eq!(family.family(pool).unwrap().to_str(pool), "Helvetica");
+        let matches = family.matchingFontDescriptorsWithMandatoryKeys(&[NSFontDescriptorAttributeName::family()], pool);
+        assert!(matches.count(pool) > 0);
+        println!("{}", descriptor.fontAttributes(pool));
+    })
+}
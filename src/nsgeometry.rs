@@ -1,2 +1,451 @@
+/*!
+`NSGeometry.h`.
+
+The functions are implemented in Rust, following Foundation's documented behavior, so they don't need an autorelease pool.
+*/
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+
+use std::ops::BitOr;
+use coregraphicsr::{CGFloat, CGPoint, CGRect, CGSize};
+use crate::typeencoding::{encode_struct, TypeEncoding};
+
 pub type NSPoint = coregraphicsr::CGPoint;
-pub type NSRect = coregraphicsr::CGRect;
\ No newline at end of file
+pub type NSSize = coregraphicsr::CGSize;
+pub type NSRect = coregraphicsr::CGRect;
+
+pub const NSZeroPoint: NSPoint = CGPoint { x: 0.0, y: 0.0 };
+pub const NSZeroSize: NSSize = CGSize { width: 0.0, height: 0.0 };
+pub const NSZeroRect: NSRect = CGRect { origin: NSZeroPoint, size: NSZeroSize };
+
+///`NSEdgeInsets`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NSEdgeInsets {
+    pub top: CGFloat,
+    pub left: CGFloat,
+    pub bottom: CGFloat,
+    pub right: CGFloat,
+}
+unsafe impl TypeEncoding for NSEdgeInsets {
+    fn encode(out: &mut String) {
+        encode_struct(out, "NSEdgeInsets", &[CGFloat::encode, CGFloat::encode, CGFloat::encode, CGFloat::encode])
+    }
+}
+pub const NSEdgeInsetsZero: NSEdgeInsets = NSEdgeInsets { top: 0.0, left: 0.0, bottom: 0.0, right: 0.0 };
+pub fn NSEdgeInsetsMake(top: CGFloat, left: CGFloat, bottom: CGFloat, right: CGFloat) -> NSEdgeInsets {
+    NSEdgeInsets { top, left, bottom, right }
+}
+pub fn NSEdgeInsetsEqual(a: NSEdgeInsets, b: NSEdgeInsets) -> bool {
+    a == b
+}
+
+///`NSRectEdge`, for [NSDivideRect].
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NSRectEdge {
+    MinX = 0,
+    MinY = 1,
+    MaxX = 2,
+    MaxY = 3,
+}
+
+/**
+`NSAlignmentOptions`, for [NSIntegralRectWithOptions].
+
+For each axis, specify exactly two of the minimum edge, the maximum edge, and the size.  The third is computed from the other two.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSAlignmentOptions(pub u64);
+impl NSAlignmentOptions {
+    pub const MIN_X_INWARD: Self = Self(1 << 0);
+    pub const MIN_Y_INWARD: Self = Self(1 << 1);
+    pub const MAX_X_INWARD: Self = Self(1 << 2);
+    pub const MAX_Y_INWARD: Self = Self(1 << 3);
+    pub const WIDTH_INWARD: Self = Self(1 << 4);
+    pub const HEIGHT_INWARD: Self = Self(1 << 5);
+
+    pub const MIN_X_OUTWARD: Self = Self(1 << 8);
+    pub const MIN_Y_OUTWARD: Self = Self(1 << 9);
+    pub const MAX_X_OUTWARD: Self = Self(1 << 10);
+    pub const MAX_Y_OUTWARD: Self = Self(1 << 11);
+    pub const WIDTH_OUTWARD: Self = Self(1 << 12);
+    pub const HEIGHT_OUTWARD: Self = Self(1 << 13);
+
+    pub const MIN_X_NEAREST: Self = Self(1 << 16);
+    pub const MIN_Y_NEAREST: Self = Self(1 << 17);
+    pub const MAX_X_NEAREST: Self = Self(1 << 18);
+    pub const MAX_Y_NEAREST: Self = Self(1 << 19);
+    pub const WIDTH_NEAREST: Self = Self(1 << 20);
+    pub const HEIGHT_NEAREST: Self = Self(1 << 21);
+
+    ///The rect is in a flipped coordinate system, so Y values halfway between integers round the other way.
+    pub const RECT_FLIPPED: Self = Self(1 << 63);
+
+    pub const ALL_EDGES_INWARD: Self = Self(Self::MIN_X_INWARD.0 | Self::MAX_X_INWARD.0 | Self::MIN_Y_INWARD.0 | Self::MAX_Y_INWARD.0);
+    pub const ALL_EDGES_OUTWARD: Self = Self(Self::MIN_X_OUTWARD.0 | Self::MAX_X_OUTWARD.0 | Self::MIN_Y_OUTWARD.0 | Self::MAX_Y_OUTWARD.0);
+    pub const ALL_EDGES_NEAREST: Self = Self(Self::MIN_X_NEAREST.0 | Self::MAX_X_NEAREST.0 | Self::MIN_Y_NEAREST.0 | Self::MAX_Y_NEAREST.0);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+impl BitOr for NSAlignmentOptions {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+pub fn NSMakePoint(x: CGFloat, y: CGFloat) -> NSPoint {
+    CGPoint { x, y }
+}
+pub fn NSMakeSize(width: CGFloat, height: CGFloat) -> NSSize {
+    CGSize { width, height }
+}
+pub fn NSMakeRect(x: CGFloat, y: CGFloat, width: CGFloat, height: CGFloat) -> NSRect {
+    CGRect { origin: NSMakePoint(x, y), size: NSMakeSize(width, height) }
+}
+
+pub fn NSMinX(rect: NSRect) -> CGFloat { rect.origin.x }
+pub fn NSMinY(rect: NSRect) -> CGFloat { rect.origin.y }
+pub fn NSMaxX(rect: NSRect) -> CGFloat { rect.origin.x + rect.size.width }
+pub fn NSMaxY(rect: NSRect) -> CGFloat { rect.origin.y + rect.size.height }
+pub fn NSMidX(rect: NSRect) -> CGFloat { rect.origin.x + rect.size.width / 2.0 }
+pub fn NSMidY(rect: NSRect) -> CGFloat { rect.origin.y + rect.size.height / 2.0 }
+pub fn NSWidth(rect: NSRect) -> CGFloat { rect.size.width }
+pub fn NSHeight(rect: NSRect) -> CGFloat { rect.size.height }
+
+pub fn NSEqualPoints(a: NSPoint, b: NSPoint) -> bool {
+    a.x == b.x && a.y == b.y
+}
+pub fn NSEqualSizes(a: NSSize, b: NSSize) -> bool {
+    a.width == b.width && a.height == b.height
+}
+pub fn NSEqualRects(a: NSRect, b: NSRect) -> bool {
+    NSEqualPoints(a.origin, b.origin) && NSEqualSizes(a.size, b.size)
+}
+///Whether the rect has no area.  Negative sizes count as empty.
+pub fn NSIsEmptyRect(rect: NSRect) -> bool {
+    !(rect.size.width > 0.0 && rect.size.height > 0.0)
+}
+
+///Shrinks the rect by `dx` on the left and right, and `dy` on the top and bottom.  Negative values grow it.
+pub fn NSInsetRect(rect: NSRect, dx: CGFloat, dy: CGFloat) -> NSRect {
+    NSMakeRect(rect.origin.x + dx, rect.origin.y + dy, rect.size.width - 2.0 * dx, rect.size.height - 2.0 * dy)
+}
+pub fn NSOffsetRect(rect: NSRect, dx: CGFloat, dy: CGFloat) -> NSRect {
+    NSMakeRect(rect.origin.x + dx, rect.origin.y + dy, rect.size.width, rect.size.height)
+}
+
+fn overlaps(a: NSRect, b: NSRect) -> bool {
+    NSMaxX(a) > NSMinX(b) && NSMaxX(b) > NSMinX(a) && NSMaxY(a) > NSMinY(b) && NSMaxY(b) > NSMinY(a)
+}
+///Returns [NSZeroRect] if the rects don't intersect.
+pub fn NSIntersectionRect(a: NSRect, b: NSRect) -> NSRect {
+    if !overlaps(a, b) {
+        return NSZeroRect
+    }
+    let x = NSMinX(a).max(NSMinX(b));
+    let y = NSMinY(a).max(NSMinY(b));
+    NSMakeRect(x, y, NSMaxX(a).min(NSMaxX(b)) - x, NSMaxY(a).min(NSMaxY(b)) - y)
+}
+pub fn NSIntersectsRect(a: NSRect, b: NSRect) -> bool {
+    !NSIsEmptyRect(a) && !NSIsEmptyRect(b) && overlaps(a, b)
+}
+///The smallest rect containing both.  Empty rects are ignored; if both are empty, returns [NSZeroRect].
+pub fn NSUnionRect(a: NSRect, b: NSRect) -> NSRect {
+    match (NSIsEmptyRect(a), NSIsEmptyRect(b)) {
+        (true, true) => NSZeroRect,
+        (true, false) => b,
+        (false, true) => a,
+        (false, false) => {
+            let x = NSMinX(a).min(NSMinX(b));
+            let y = NSMinY(a).min(NSMinY(b));
+            NSMakeRect(x, y, NSMaxX(a).max(NSMaxX(b)) - x, NSMaxY(a).max(NSMaxY(b)) - y)
+        }
+    }
+}
+/**
+Splits `rect` into a slice `amount` wide (or tall) along `edge`, and the remainder.  Returns `(slice, remainder)`.
+
+`amount` is clamped to the rect, so the slice never extends past it.
+*/
+pub fn NSDivideRect(rect: NSRect, amount: CGFloat, edge: NSRectEdge) -> (NSRect, NSRect) {
+    let (x, y, w, h) = (rect.origin.x, rect.origin.y, rect.size.width, rect.size.height);
+    match edge {
+        NSRectEdge::MinX => {
+            let amount = amount.max(0.0).min(w);
+            (NSMakeRect(x, y, amount, h), NSMakeRect(x + amount, y, w - amount, h))
+        }
+        NSRectEdge::MaxX => {
+            let amount = amount.max(0.0).min(w);
+            (NSMakeRect(x + w - amount, y, amount, h), NSMakeRect(x, y, w - amount, h))
+        }
+        NSRectEdge::MinY => {
+            let amount = amount.max(0.0).min(h);
+            (NSMakeRect(x, y, w, amount), NSMakeRect(x, y + amount, w, h - amount))
+        }
+        NSRectEdge::MaxY => {
+            let amount = amount.max(0.0).min(h);
+            (NSMakeRect(x, y + h - amount, w, amount), NSMakeRect(x, y, w, h - amount))
+        }
+    }
+}
+///The smallest integral rect containing `rect`, or [NSZeroRect] if `rect` is empty.
+pub fn NSIntegralRect(rect: NSRect) -> NSRect {
+    if NSIsEmptyRect(rect) {
+        return NSZeroRect
+    }
+    let x = NSMinX(rect).floor();
+    let y = NSMinY(rect).floor();
+    NSMakeRect(x, y, NSMaxX(rect).ceil() - x, NSMaxY(rect).ceil() - y)
+}
+
+#[derive(Clone, Copy)]
+enum Rounding {
+    Inward,
+    Outward,
+    Nearest,
+}
+
+///Aligns one axis.  Returns `(min, length)`.
+fn align_axis(min: CGFloat, length: CGFloat, options: NSAlignmentOptions, flags: [[NSAlignmentOptions; 3]; 3], flipped: bool, axis: &str) -> (CGFloat, CGFloat) {
+    //flags is [min, max, length] × [inward, outward, nearest]
+    let pick = |flags: [NSAlignmentOptions; 3], name: &str| -> Option<Rounding> {
+        let mut picked = None;
+        for (rounding, flag) in [Rounding::Inward, Rounding::Outward, Rounding::Nearest].into_iter().zip(flags) {
+            if options.contains(flag) {
+                assert!(picked.is_none(), "conflicting alignment options for {} {}", axis, name);
+                picked = Some(rounding);
+            }
+        }
+        picked
+    };
+    let nearest = |v: CGFloat| if flipped { (v - 0.5).ceil() } else { (v + 0.5).floor() };
+    let max = min + length;
+    let round_min = |r| match r { Rounding::Inward => min.ceil(), Rounding::Outward => min.floor(), Rounding::Nearest => nearest(min) };
+    let round_max = |r| match r { Rounding::Inward => max.floor(), Rounding::Outward => max.ceil(), Rounding::Nearest => nearest(max) };
+    let round_length = |r| match r { Rounding::Inward => length.floor(), Rounding::Outward => length.ceil(), Rounding::Nearest => (length + 0.5).floor() };
+    match (pick(flags[0], "min"), pick(flags[1], "max"), pick(flags[2], "size")) {
+        (Some(a), Some(b), None) => {
+            let min = round_min(a);
+            (min, round_max(b) - min)
+        }
+        (Some(a), None, Some(c)) => (round_min(a), round_length(c)),
+        (None, Some(b), Some(c)) => {
+            let length = round_length(c);
+            (round_max(b) - length, length)
+        }
+        _ => panic!("alignment options must specify exactly two of min, max and size for {}", axis)
+    }
+}
+
+/**
+Rounds the rect's edges to integers as `options` specify.
+
+# Panics
+If `options` doesn't specify exactly two of the minimum edge, maximum edge and size for each axis, or specifies
+more than one rounding for the same value.
+*/
+pub fn NSIntegralRectWithOptions(rect: NSRect, options: NSAlignmentOptions) -> NSRect {
+    type O = NSAlignmentOptions;
+    let flipped = options.contains(O::RECT_FLIPPED);
+    let (x, width) = align_axis(rect.origin.x, rect.size.width, options, [
+        [O::MIN_X_INWARD, O::MIN_X_OUTWARD, O::MIN_X_NEAREST],
+        [O::MAX_X_INWARD, O::MAX_X_OUTWARD, O::MAX_X_NEAREST],
+        [O::WIDTH_INWARD, O::WIDTH_OUTWARD, O::WIDTH_NEAREST],
+    ], false, "x");
+    let (y, height) = align_axis(rect.origin.y, rect.size.height, options, [
+        [O::MIN_Y_INWARD, O::MIN_Y_OUTWARD, O::MIN_Y_NEAREST],
+        [O::MAX_Y_INWARD, O::MAX_Y_OUTWARD, O::MAX_Y_NEAREST],
+        [O::HEIGHT_INWARD, O::HEIGHT_OUTWARD, O::HEIGHT_NEAREST],
+    ], flipped, "y");
+    NSMakeRect(x, y, width, height)
+}
+
+///Whether `point` is inside `rect`.  Points on the minimum edges are inside, and points on the maximum edges are not.
+pub fn NSPointInRect(point: NSPoint, rect: NSRect) -> bool {
+    point.x >= NSMinX(rect) && point.x < NSMaxX(rect) && point.y >= NSMinY(rect) && point.y < NSMaxY(rect)
+}
+///Whether `a` completely encloses `b`.  Always `false` if `b` is empty.
+pub fn NSContainsRect(a: NSRect, b: NSRect) -> bool {
+    !NSIsEmptyRect(b) && NSMinX(a) <= NSMinX(b) && NSMinY(a) <= NSMinY(b) && NSMaxX(a) >= NSMaxX(b) && NSMaxY(a) >= NSMaxY(b)
+}
+
+/**
+Formats as `{x, y}`.
+
+The numbers use Rust's shortest round-trip formatting, not Foundation's `%g`.  They agree for typical values like `10`,
+`1.5` and `-0.25`, but not in general: `1e20` formats as `100000000000000000000` and `0.1 + 0.2` as
+`0.30000000000000004`.  In exchange, [NSPointFromString] reads back exactly the same value.
+*/
+pub fn NSStringFromPoint(point: NSPoint) -> String {
+    format!("{{{}, {}}}", point.x, point.y)
+}
+///Formats as `{width, height}`, with numbers formatted like [NSStringFromPoint].
+pub fn NSStringFromSize(size: NSSize) -> String {
+    format!("{{{}, {}}}", size.width, size.height)
+}
+///Formats as `{{x, y}, {width, height}}`, with numbers formatted like [NSStringFromPoint].
+pub fn NSStringFromRect(rect: NSRect) -> String {
+    format!("{{{}, {}}}", NSStringFromPoint(rect.origin), NSStringFromSize(rect.size))
+}
+
+///The length of the decimal number (like `-1.5e3`) at the start of `bytes`, or 0 if there isn't one.
+fn number_length(bytes: &[u8]) -> usize {
+    let digits = |from: usize| bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let mut end = 0;
+    if matches!(bytes.first(), Some(b'-' | b'+')) {
+        end += 1;
+    }
+    let integer = digits(end);
+    end += integer;
+    let mut fraction = 0;
+    if bytes.get(end) == Some(&b'.') {
+        fraction = digits(end + 1);
+        end += 1 + fraction;
+    }
+    if integer + fraction == 0 {
+        return 0
+    }
+    //an exponent only counts if it has digits
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = matches!(bytes.get(end + 1), Some(b'-' | b'+')) as usize;
+        let exponent = digits(end + 1 + sign);
+        if exponent > 0 {
+            end += 1 + sign + exponent;
+        }
+    }
+    end
+}
+///Scans the first `N` numbers out of `string`, leniently like Foundation.  Missing numbers are 0.
+fn scan_numbers<const N: usize>(string: &str) -> [CGFloat; N] {
+    let mut out = [0.0; N];
+    let bytes = string.as_bytes();
+    let mut offset = 0;
+    for value in out.iter_mut() {
+        loop {
+            if offset == bytes.len() {
+                return out
+            }
+            match number_length(&bytes[offset..]) {
+                //skip anything that doesn't start a number, including a sign or dot on its own
+                0 => offset += 1,
+                length => {
+                    //the number is ASCII, so this slice is on char boundaries
+                    *value = string[offset..offset + length].parse().unwrap();
+                    offset += length;
+                    break
+                }
+            }
+        }
+    }
+    out
+}
+///Parses a point formatted like [NSStringFromPoint].  Lenient like Foundation: missing values are 0.
+pub fn NSPointFromString(string: &str) -> NSPoint {
+    let [x, y] = scan_numbers(string);
+    NSMakePoint(x, y)
+}
+///Parses a size formatted like [NSStringFromSize].  Lenient like Foundation: missing values are 0.
+pub fn NSSizeFromString(string: &str) -> NSSize {
+    let [width, height] = scan_numbers(string);
+    NSMakeSize(width, height)
+}
+///Parses a rect formatted like [NSStringFromRect].  Lenient like Foundation: missing values are 0.
+pub fn NSRectFromString(string: &str) -> NSRect {
+    let [x, y, width, height] = scan_numbers(string);
+    NSMakeRect(x, y, width, height)
+}
+
+#[cfg(test)]
+fn assert_rect(actual: NSRect, expected: NSRect) {
+    assert!(NSEqualRects(actual, expected), "{} != {}", NSStringFromRect(actual), NSStringFromRect(expected));
+}
+
+#[test] fn rect_functions() {
+    let rect = NSMakeRect(10.0, 20.0, 30.0, 40.0);
+    assert_eq!((NSMinX(rect), NSMidX(rect), NSMaxX(rect)), (10.0, 25.0, 40.0));
+    assert_eq!((NSMinY(rect), NSMidY(rect), NSMaxY(rect)), (20.0, 40.0, 60.0));
+    assert!(NSIsEmptyRect(NSZeroRect));
+    assert!(NSIsEmptyRect(NSMakeRect(0.0, 0.0, -1.0, 5.0)));
+
+    assert_rect(NSInsetRect(rect, 5.0, 10.0), NSMakeRect(15.0, 30.0, 20.0, 20.0));
+    assert_rect(NSInsetRect(rect, -5.0, 0.0), NSMakeRect(5.0, 20.0, 40.0, 40.0));
+    assert_rect(NSOffsetRect(rect, -10.0, 1.0), NSMakeRect(0.0, 21.0, 30.0, 40.0));
+
+    let other = NSMakeRect(30.0, 50.0, 100.0, 100.0);
+    assert_rect(NSIntersectionRect(rect, other), NSMakeRect(30.0, 50.0, 10.0, 10.0));
+    assert!(NSIntersectsRect(rect, other));
+    let touching = NSMakeRect(40.0, 20.0, 10.0, 10.0);
+    assert_rect(NSIntersectionRect(rect, touching), NSZeroRect);
+    assert!(!NSIntersectsRect(rect, touching));
+
+    assert_rect(NSUnionRect(rect, other), NSMakeRect(10.0, 20.0, 120.0, 130.0));
+    assert_rect(NSUnionRect(rect, NSZeroRect), rect);
+    assert_rect(NSUnionRect(NSZeroRect, NSMakeRect(5.0, 5.0, 0.0, 3.0)), NSZeroRect);
+
+    assert!(NSPointInRect(NSMakePoint(10.0, 20.0), rect));
+    assert!(!NSPointInRect(NSMakePoint(40.0, 30.0), rect));
+    assert!(NSContainsRect(rect, NSMakeRect(10.0, 20.0, 30.0, 40.0)));
+    assert!(!NSContainsRect(rect, other));
+    assert!(!NSContainsRect(rect, NSMakeRect(15.0, 25.0, 0.0, 0.0)));
+}
+
+#[test] fn divide() {
+    let rect = NSMakeRect(0.0, 0.0, 100.0, 50.0);
+    let (slice, remainder) = NSDivideRect(rect, 30.0, NSRectEdge::MinX);
+    assert_rect(slice, NSMakeRect(0.0, 0.0, 30.0, 50.0));
+    assert_rect(remainder, NSMakeRect(30.0, 0.0, 70.0, 50.0));
+    let (slice, remainder) = NSDivideRect(rect, 30.0, NSRectEdge::MaxX);
+    assert_rect(slice, NSMakeRect(70.0, 0.0, 30.0, 50.0));
+    assert_rect(remainder, NSMakeRect(0.0, 0.0, 70.0, 50.0));
+    let (slice, remainder) = NSDivideRect(rect, 10.0, NSRectEdge::MinY);
+    assert_rect(slice, NSMakeRect(0.0, 0.0, 100.0, 10.0));
+    assert_rect(remainder, NSMakeRect(0.0, 10.0, 100.0, 40.0));
+    let (slice, remainder) = NSDivideRect(rect, 80.0, NSRectEdge::MaxY);
+    assert_rect(slice, rect);
+    assert_rect(remainder, NSMakeRect(0.0, 0.0, 100.0, 0.0));
+}
+
+#[test] fn integral() {
+    let rect = NSMakeRect(0.5, 1.25, 10.5, 2.5);
+    assert_rect(NSIntegralRect(rect), NSMakeRect(0.0, 1.0, 11.0, 3.0));
+    assert_rect(NSIntegralRect(NSMakeRect(0.5, 0.5, 0.0, 1.0)), NSZeroRect);
+
+    assert_rect(NSIntegralRectWithOptions(rect, NSAlignmentOptions::ALL_EDGES_OUTWARD), NSMakeRect(0.0, 1.0, 11.0, 3.0));
+    assert_rect(NSIntegralRectWithOptions(rect, NSAlignmentOptions::ALL_EDGES_INWARD), NSMakeRect(1.0, 2.0, 10.0, 1.0));
+    assert_rect(NSIntegralRectWithOptions(rect, NSAlignmentOptions::ALL_EDGES_NEAREST), NSMakeRect(1.0, 1.0, 10.0, 3.0));
+    assert_rect(NSIntegralRectWithOptions(rect, NSAlignmentOptions::ALL_EDGES_NEAREST | NSAlignmentOptions::RECT_FLIPPED), NSMakeRect(1.0, 1.0, 10.0, 3.0));
+    assert_rect(NSIntegralRectWithOptions(NSMakeRect(0.0, 0.5, 1.0, 1.0), NSAlignmentOptions::ALL_EDGES_NEAREST | NSAlignmentOptions::RECT_FLIPPED), NSMakeRect(0.0, 0.0, 1.0, 1.0));
+
+    type O = NSAlignmentOptions;
+    let options = O::MIN_X_NEAREST | O::WIDTH_INWARD | O::MAX_Y_OUTWARD | O::HEIGHT_NEAREST;
+    assert_rect(NSIntegralRectWithOptions(rect, options), NSMakeRect(1.0, 1.0, 10.0, 3.0));
+}
+
+#[test] #[should_panic(expected = "exactly two")] fn integral_invalid_options() {
+    NSIntegralRectWithOptions(NSZeroRect, NSAlignmentOptions::MIN_X_INWARD | NSAlignmentOptions::ALL_EDGES_INWARD | NSAlignmentOptions::WIDTH_INWARD);
+}
+
+#[test] fn strings() {
+    let rect = NSMakeRect(0.0, -1.5, 10.0, 0.25);
+    assert_eq!(NSStringFromRect(rect), "{{0, -1.5}, {10, 0.25}}");
+    assert_rect(NSRectFromString(&NSStringFromRect(rect)), rect);
+    assert_eq!(NSStringFromPoint(NSMakePoint(1.0, 2.0)), "{1, 2}");
+    assert!(NSEqualPoints(NSPointFromString("{1, 2}"), NSMakePoint(1.0, 2.0)));
+    assert!(NSEqualSizes(NSSizeFromString("{3,4e2}"), NSMakeSize(3.0, 400.0)));
+    assert!(NSEqualPoints(NSPointFromString("{7"), NSMakePoint(7.0, 0.0)));
+    assert_rect(NSRectFromString("garbage"), NSZeroRect);
+    assert!(NSEqualPoints(NSPointFromString("{- , .5}"), NSMakePoint(0.5, 0.0)));
+    assert!(NSEqualPoints(NSPointFromString("{-inf, nan}"), NSZeroPoint));
+    assert!(NSEqualPoints(NSPointFromString("{1e, 2.e-1}"), NSMakePoint(1.0, 0.2)));
+
+    let point = NSMakePoint(1e20, 0.1 + 0.2);
+    assert_eq!(NSStringFromPoint(point), "{100000000000000000000, 0.30000000000000004}");
+    assert!(NSEqualPoints(NSPointFromString(&NSStringFromPoint(point)), point));
+
+    assert_eq!(NSEdgeInsets::encoding(), "{NSEdgeInsets=dddd}");
+    assert!(NSEdgeInsetsEqual(NSEdgeInsetsMake(0.0, 0.0, 0.0, 0.0), NSEdgeInsetsZero));
+}
@@ -0,0 +1,52 @@
+use objr::bindings::*;
+
+objc_class! {
+    pub struct NSLocale {
+        @class(NSLocale)
+    }
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("localeWithLocaleIdentifier:")
+        @selector("currentLocale")
+        @selector("localeIdentifier")
+    }
+    impl Selectors for Sel {}
+}
+
+//immutable
+unsafe impl Send for NSLocale {}
+unsafe impl Sync for NSLocale {}
+
+#[allow(non_snake_case)]
+impl NSLocale {
+    pub fn localeWithLocaleIdentifier(identifier: &NSString, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::localeWithLocaleIdentifier_(), pool, (identifier.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///`en_US_POSIX`, for formatting and parsing fixed-format dates (such as in HTTP headers) regardless of the user's settings.
+    pub fn en_us_posix(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::localeWithLocaleIdentifier(objc_nsstring!("en_US_POSIX"), pool)
+    }
+    pub fn currentLocale(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::currentLocale(), pool, ());
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn localeIdentifier(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::localeIdentifier(), pool, ());
+            NSString::assume_nonnil(raw).assume_retained()
+        }
+    }
+}
+
+#[test] fn locales() {
+    autoreleasepool(|pool| {
+        assert_eq!(NSLocale::en_us_posix(pool).localeIdentifier(pool).to_str(pool), "en_US_POSIX");
+        NSLocale::currentLocale(pool).localeIdentifier(pool);
+    })
+}
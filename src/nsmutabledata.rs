@@ -0,0 +1,265 @@
+use objr::bindings::*;
+use crate::{NSData, NSDataCompressionAlgorithm, NSInteger, NSRange, NSUInteger};
+
+objc_class! {
+    pub struct NSMutableData {
+        @class(NSMutableData)
+    }
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("initWithCapacity:")
+        @selector("initWithLength:")
+        @selector("initWithData:")
+        @selector("mutableBytes")
+        @selector("appendBytes:length:")
+        @selector("appendData:")
+        @selector("setLength:")
+        @selector("increaseLengthBy:")
+        @selector("replaceBytesInRange:withBytes:")
+        @selector("resetBytesInRange:")
+        @selector("compressUsingAlgorithm:error:")
+        @selector("decompressUsingAlgorithm:error:")
+    }
+    impl Selectors for Sel {}
+}
+
+/**
+`NSMutableData`.
+
+Foundation raises `NSRangeException` for out-of-bounds ranges, so these methods check bounds first and panic instead.
+
+To stream bytes in with [std::io::Write] or [Extend], use [NSMutableData::writer].
+*/
+#[allow(non_snake_case)]
+impl NSMutableData {
+    pub fn new(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            Self::class().alloc_init(pool).assume_mut()
+        }
+    }
+    ///An empty buffer with room for `capacity` bytes.  Foundation treats this as a hint.
+    pub fn withCapacity(capacity: NSUInteger, pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithCapacity_(), pool, (capacity,));
+            Self::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    ///`length` zeroed bytes.
+    pub fn withLength(length: NSUInteger, pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithLength_(), pool, (length,));
+            Self::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    ///A mutable copy of `data`.
+    pub fn withData(data: &NSData, pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithData_(), pool, (data.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    ///A mutable copy of `bytes`.
+    pub fn with_slice(bytes: &[u8], pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        let mut data = Self::withCapacity(bytes.len() as NSUInteger, pool);
+        data.appendBytes(bytes, pool);
+        data
+    }
+    pub fn length(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        self.as_immutable().length(pool)
+    }
+    pub fn as_slice(&self, pool: &ActiveAutoreleasePool) -> &[u8] {
+        self.as_immutable().as_slice(pool)
+    }
+    ///`mutableBytes`.  Changing the length invalidates this slice, which the borrow on `self` enforces.
+    pub fn as_mut_slice(&mut self, pool: &ActiveAutoreleasePool) -> &mut [u8] {
+        let length = self.length(pool) as usize;
+        if length == 0 {
+            //mutableBytes may be NULL
+            return &mut [];
+        }
+        unsafe {
+            let ptr: *mut u8 = Self::perform_primitive(self, Sel::mutableBytes(), pool, ());
+            std::slice::from_raw_parts_mut(ptr, length)
+        }
+    }
+    fn check_range(&self, range: NSRange, pool: &ActiveAutoreleasePool) {
+        let length = self.length(pool);
+        assert!(range.checked_max().map(|end| end <= length).unwrap_or(false), "range {} out of bounds for length {}", range, length);
+    }
+    ///`appendBytes:length:`
+    pub fn appendBytes(&mut self, bytes: &[u8], pool: &ActiveAutoreleasePool) {
+        if bytes.is_empty() {
+            return;
+        }
+        unsafe {
+            Self::perform_primitive(self, Sel::appendBytes_length(), pool, (bytes.as_ptr().assume_nonmut_perform(), bytes.len() as NSUInteger))
+        }
+    }
+    pub fn appendData(&mut self, data: &NSData, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::appendData_(), pool, (data.assume_nonmut_perform(),))
+        }
+    }
+    ///Truncates, or extends with zeroes.
+    pub fn setLength(&mut self, length: NSUInteger, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setLength_(), pool, (length,))
+        }
+    }
+    ///Extends with `extra_length` zeroes.
+    pub fn increaseLengthBy(&mut self, extra_length: NSUInteger, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::increaseLengthBy_(), pool, (extra_length,))
+        }
+    }
+    ///Overwrites `range` with `bytes`, which must be the same length.
+    ///# Panics
+    /// If `range` is out of bounds, or `bytes.len()` is not `range.length`.
+    pub fn replaceBytesInRangeWithBytes(&mut self, range: NSRange, bytes: &[u8], pool: &ActiveAutoreleasePool) {
+        self.check_range(range, pool);
+        assert_eq!(bytes.len() as NSUInteger, range.length, "replacement length does not match range {}", range);
+        unsafe {
+            Self::perform_primitive(self, Sel::replaceBytesInRange_withBytes(), pool, (range, bytes.as_ptr().assume_nonmut_perform()))
+        }
+    }
+    ///Zeroes `range`.
+    ///# Panics
+    /// If `range` is out of bounds.
+    pub fn resetBytesInRange(&mut self, range: NSRange, pool: &ActiveAutoreleasePool) {
+        self.check_range(range, pool);
+        unsafe {
+            Self::perform_primitive(self, Sel::resetBytesInRange_(), pool, (range,))
+        }
+    }
+    ///Compresses in place.  On failure, the contents are unchanged.  Requires macOS 10.15.
+    pub fn compressUsingAlgorithm(&mut self, algorithm: NSDataCompressionAlgorithm, pool: &ActiveAutoreleasePool) -> Result<(), StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let success: bool = Self::perform_primitive(self, Sel::compressUsingAlgorithm_error(), pool, (algorithm as NSInteger, &mut error as *mut *const NSError));
+            if success { Ok(()) } else { Err(NSError::assume_nonnil(error).retain().assume_mut()) }
+        }
+    }
+    ///Decompresses in place.  On failure, the contents are unchanged.  Requires macOS 10.15.
+    pub fn decompressUsingAlgorithm(&mut self, algorithm: NSDataCompressionAlgorithm, pool: &ActiveAutoreleasePool) -> Result<(), StrongMutCell<NSError>> {
+        unsafe {
+            let mut error: *const NSError = std::ptr::null();
+            let success: bool = Self::perform_primitive(self, Sel::decompressUsingAlgorithm_error(), pool, (algorithm as NSInteger, &mut error as *mut *const NSError));
+            if success { Ok(()) } else { Err(NSError::assume_nonnil(error).retain().assume_mut()) }
+        }
+    }
+    ///Appends through [std::io::Write] or [Extend].
+    pub fn writer<'a>(&'a mut self, pool: &'a ActiveAutoreleasePool) -> NSMutableDataWriter<'a> {
+        NSMutableDataWriter { data: self, pool }
+    }
+}
+objc_cast!(NSMutableData, unsafe NSData, as_immutable, as_immutable_mut);
+
+/**
+Appends to an [NSMutableData].  Create with [NSMutableData::writer].
+
+Writes never fail.  `Extend` copies through a small stack buffer, so extending from an iterator
+doesn't send a message per byte.
+*/
+pub struct NSMutableDataWriter<'a> {
+    data: &'a mut NSMutableData,
+    pool: &'a ActiveAutoreleasePool,
+}
+impl<'a> std::io::Write for NSMutableDataWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.data.appendBytes(buf, self.pool);
+        Ok(buf.len())
+    }
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.data.appendBytes(buf, self.pool);
+        Ok(())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+impl<'a> Extend<u8> for NSMutableDataWriter<'a> {
+    fn extend<T: IntoIterator<Item=u8>>(&mut self, iter: T) {
+        let mut buffer = [0u8; 1024];
+        let mut filled = 0;
+        for byte in iter {
+            buffer[filled] = byte;
+            filled += 1;
+            if filled == buffer.len() {
+                self.data.appendBytes(&buffer, self.pool);
+                filled = 0;
+            }
+        }
+        self.data.appendBytes(&buffer[..filled], self.pool);
+    }
+}
+impl<'a, 'b> Extend<&'b u8> for NSMutableDataWriter<'a> {
+    fn extend<T: IntoIterator<Item=&'b u8>>(&mut self, iter: T) {
+        Extend::<u8>::extend(self, iter.into_iter().copied())
+    }
+}
+
+#[test] fn mutable_data() {
+    autoreleasepool(|pool| {
+        let mut data = NSMutableData::new(pool);
+        assert!(data.as_mut_slice(pool).is_empty());
+        data.appendBytes(b"hello", pool);
+        data.appendData(NSMutableData::with_slice(b" world", pool).as_immutable(), pool);
+        assert_eq!(data.as_slice(pool), b"hello world");
+
+        data.replaceBytesInRangeWithBytes(NSRange { location: 0, length: 1 }, b"j", pool);
+        data.as_mut_slice(pool)[10] = b'D';
+        assert_eq!(data.as_slice(pool), b"jello worlD");
+
+        data.setLength(5, pool);
+        data.increaseLengthBy(2, pool);
+        assert_eq!(data.as_slice(pool), b"jello\0\0");
+        data.resetBytesInRange(NSRange { location: 0, length: 2 }, pool);
+        assert_eq!(data.as_slice(pool), b"\0\0llo\0\0");
+
+        let zeroed = NSMutableData::withLength(3, pool);
+        assert_eq!(zeroed.as_slice(pool), &[0, 0, 0]);
+        let copy = NSMutableData::withData(zeroed.as_immutable(), pool);
+        assert_eq!(copy.length(pool), 3);
+    })
+}
+
+#[test] fn mutable_data_writer() {
+    use std::io::Write;
+    autoreleasepool(|pool| {
+        let mut data = NSMutableData::withCapacity(16, pool);
+        let mut writer = data.writer(pool);
+        write!(writer, "{}-{}", 1, "two").unwrap();
+        writer.extend((0..3000u32).map(|i| (i % 251) as u8));
+        writer.extend(b"!".iter());
+        writer.flush().unwrap();
+        let slice = data.as_slice(pool);
+        assert_eq!(slice.len(), 5 + 3000 + 1);
+        assert_eq!(&slice[..5], b"1-two");
+        assert!(slice[5..3005].iter().enumerate().all(|(i, b)| *b == (i % 251) as u8));
+        assert_eq!(slice[3005], b'!');
+    })
+}
+
+#[test] #[should_panic] fn replace_length_mismatch() {
+    autoreleasepool(|pool| {
+        let mut data = NSMutableData::with_slice(b"abc", pool);
+        data.replaceBytesInRangeWithBytes(NSRange { location: 0, length: 2 }, b"x", pool);
+    })
+}
+
+#[test] fn compress_in_place() {
+    autoreleasepool(|pool| {
+        let original = b"squeeze ".repeat(64);
+        let mut data = NSMutableData::with_slice(&original, pool);
+        data.compressUsingAlgorithm(NSDataCompressionAlgorithm::Zlib, pool).unwrap();
+        assert!(data.length(pool) < original.len() as NSUInteger);
+        data.decompressUsingAlgorithm(NSDataCompressionAlgorithm::Zlib, pool).unwrap();
+        assert_eq!(data.as_slice(pool), &original[..]);
+        let base64 = data.as_immutable().base64EncodedStringWithOptions(Default::default(), pool);
+        assert!(base64.to_str(pool).starts_with("c3F1ZWV6ZSBz"));
+    })
+}
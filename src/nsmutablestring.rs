@@ -0,0 +1,122 @@
+use objr::bindings::*;
+use crate::{NSRange, NSStringCompareOptions, NSStringExtension, NSUInteger};
+
+objc_class! {
+    pub struct NSMutableString {
+        @class(NSMutableString)
+    }
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("initWithString:")
+        @selector("setString:")
+        @selector("appendString:")
+        @selector("insertString:atIndex:")
+        @selector("deleteCharactersInRange:")
+        @selector("replaceCharactersInRange:withString:")
+        @selector("replaceOccurrencesOfString:withString:options:range:")
+    }
+    impl Selectors for Sel {}
+}
+
+/**
+`NSMutableString`.
+
+Indices and ranges are in UTF-16 code units.  Foundation raises `NSRangeException` for out-of-bounds
+arguments, so these methods check bounds first and panic instead.
+*/
+#[allow(non_snake_case)]
+impl NSMutableString {
+    pub fn new(pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            Self::class().alloc_init(pool).assume_mut()
+        }
+    }
+    pub fn withString(string: &NSString, pool: &ActiveAutoreleasePool) -> StrongMutCell<Self> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithString_(), pool, (string.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained().assume_mut()
+        }
+    }
+    fn check_range(&self, range: NSRange, pool: &ActiveAutoreleasePool) {
+        let length = self.as_immutable().length(pool);
+        assert!(range.checked_max().map(|end| end <= length).unwrap_or(false), "range {} out of bounds for length {}", range, length);
+    }
+    pub fn setString(&mut self, string: &NSString, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::setString_(), pool, (string.assume_nonmut_perform(),))
+        }
+    }
+    pub fn appendString(&mut self, string: &NSString, pool: &ActiveAutoreleasePool) {
+        unsafe {
+            Self::perform_primitive(self, Sel::appendString_(), pool, (string.assume_nonmut_perform(),))
+        }
+    }
+    ///# Panics
+    /// If `index` is greater than the length of the receiver.
+    pub fn insertStringAtIndex(&mut self, string: &NSString, index: NSUInteger, pool: &ActiveAutoreleasePool) {
+        self.check_range(NSRange { location: index, length: 0 }, pool);
+        unsafe {
+            Self::perform_primitive(self, Sel::insertString_atIndex(), pool, (string.assume_nonmut_perform(), index))
+        }
+    }
+    ///# Panics
+    /// If `range` is out of bounds.
+    pub fn deleteCharactersInRange(&mut self, range: NSRange, pool: &ActiveAutoreleasePool) {
+        self.check_range(range, pool);
+        unsafe {
+            Self::perform_primitive(self, Sel::deleteCharactersInRange_(), pool, (range,))
+        }
+    }
+    ///# Panics
+    /// If `range` is out of bounds.
+    pub fn replaceCharactersInRangeWithString(&mut self, range: NSRange, string: &NSString, pool: &ActiveAutoreleasePool) {
+        self.check_range(range, pool);
+        unsafe {
+            Self::perform_primitive(self, Sel::replaceCharactersInRange_withString(), pool, (range, string.assume_nonmut_perform()))
+        }
+    }
+    ///Returns the number of replacements made.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds.
+    pub fn replaceOccurrencesOfStringWithStringOptionsRange(&mut self, target: &NSString, replacement: &NSString, options: NSStringCompareOptions, range: NSRange, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        self.check_range(range, pool);
+        unsafe {
+            Self::perform_primitive(self, Sel::replaceOccurrencesOfString_withString_options_range(), pool, (target.assume_nonmut_perform(), replacement.assume_nonmut_perform(), options.0, range))
+        }
+    }
+}
+objc_cast!(NSMutableString, unsafe NSString, as_immutable, as_immutable_mut);
+
+#[test] fn smoke() {
+    autoreleasepool(|pool| {
+        let mut string = NSMutableString::withString(objc_nsstring!("world"), pool);
+        string.insertStringAtIndex(objc_nsstring!("hello "), 0, pool);
+        string.appendString(objc_nsstring!("!"), pool);
+        assert_eq!(string.as_immutable().to_str(pool), "hello world!");
+
+        string.deleteCharactersInRange(NSRange { location: 0, length: 6 }, pool);
+        assert_eq!(string.as_immutable().to_str(pool), "world!");
+
+        string.replaceCharactersInRangeWithString(NSRange { location: 5, length: 1 }, objc_nsstring!("?"), pool);
+        assert_eq!(string.as_immutable().to_str(pool), "world?");
+
+        let length = string.as_immutable().length(pool);
+        let replaced = string.replaceOccurrencesOfStringWithStringOptionsRange(objc_nsstring!("O"), objc_nsstring!("0"), NSStringCompareOptions::CASE_INSENSITIVE, NSRange { location: 0, length }, pool);
+        assert_eq!(replaced, 1);
+        assert_eq!(string.as_immutable().to_str(pool), "w0rld?");
+
+        let mut empty = NSMutableString::new(pool);
+        empty.setString(objc_nsstring!("reset"), pool);
+        assert_eq!(empty.as_immutable().to_str(pool), "reset");
+    })
+}
+
+#[test] #[should_panic] fn out_of_bounds() {
+    autoreleasepool(|pool| {
+        let mut string = NSMutableString::withString(objc_nsstring!("abc"), pool);
+        string.deleteCharactersInRange(NSRange { location: 2, length: 2 }, pool);
+    })
+}
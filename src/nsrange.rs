@@ -1,12 +1,430 @@
 //!nsrange.h
 
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
 use objr::bindings::Arguable;
-use crate::NSUInteger;
+use crate::{NSInteger, NSUInteger};
+
+///`NSNotFound`, the location Foundation returns when a search fails.
+#[allow(non_upper_case_globals)]
+pub const NSNotFound: NSUInteger = NSInteger::MAX as NSUInteger;
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct NSRange {
     pub location: NSUInteger,
     pub length: NSUInteger,
 }
-unsafe impl Arguable for NSRange {}
\ No newline at end of file
+unsafe impl Arguable for NSRange {}
+
+impl NSRange {
+    ///The range Foundation returns from a failed search.
+    pub const NOT_FOUND: NSRange = NSRange { location: NSNotFound, length: 0 };
+
+    ///Creates a range, returning `None` if `location + length` overflows.
+    pub const fn new(location: NSUInteger, length: NSUInteger) -> Option<Self> {
+        match location.checked_add(length) {
+            Some(_) => Some(NSRange { location, length }),
+            None => None,
+        }
+    }
+    ///`location + length`, or `None` if that overflows.
+    pub const fn checked_max(self) -> Option<NSUInteger> {
+        self.location.checked_add(self.length)
+    }
+    ///True if the location is [NSNotFound].
+    pub const fn is_not_found(self) -> bool {
+        self.location == NSNotFound
+    }
+    ///Maps the [NSNotFound] sentinel to `None`.
+    pub const fn found(self) -> Option<Self> {
+        if self.is_not_found() { None } else { Some(self) }
+    }
+    pub const fn is_empty(self) -> bool {
+        self.length == 0
+    }
+    ///See [NSMaxRange].
+    pub fn max(self) -> NSUInteger {
+        NSMaxRange(self)
+    }
+    ///See [NSLocationInRange].
+    pub fn contains(self, location: NSUInteger) -> bool {
+        NSLocationInRange(location, self)
+    }
+    ///See [NSUnionRange].
+    pub fn union(self, other: NSRange) -> NSRange {
+        NSUnionRange(self, other)
+    }
+    ///See [NSIntersectionRange].
+    pub fn intersection(self, other: NSRange) -> NSRange {
+        NSIntersectionRange(self, other)
+    }
+}
+
+///`location + length`.
+///
+/// # Panics
+/// If the sum overflows.
+#[allow(non_snake_case)]
+pub fn NSMaxRange(range: NSRange) -> NSUInteger {
+    range.checked_max().expect("NSRange overflows NSUInteger")
+}
+
+#[allow(non_snake_case)]
+pub fn NSLocationInRange(location: NSUInteger, range: NSRange) -> bool {
+    location >= range.location && location - range.location < range.length
+}
+
+///The smallest range containing both ranges.
+#[allow(non_snake_case)]
+pub fn NSUnionRange(range1: NSRange, range2: NSRange) -> NSRange {
+    let max = NSMaxRange(range1).max(NSMaxRange(range2));
+    let location = range1.location.min(range2.location);
+    NSRange { location, length: max - location }
+}
+
+///The overlap of both ranges, or `{0, 0}` if they don't overlap.
+#[allow(non_snake_case)]
+pub fn NSIntersectionRange(range1: NSRange, range2: NSRange) -> NSRange {
+    let max1 = NSMaxRange(range1);
+    let max2 = NSMaxRange(range2);
+    if max1 < range2.location || max2 < range1.location {
+        return NSRange { location: 0, length: 0 }
+    }
+    let location = range1.location.max(range2.location);
+    NSRange { location, length: max1.min(max2) - location }
+}
+
+///Formats as `{location, length}`, like `NSStringFromRange`.
+impl Display for NSRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{{}, {}}}", self.location, self.length)
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn NSStringFromRange(range: NSRange) -> String {
+    range.to_string()
+}
+
+/**
+Parses a range the way `NSRangeFromString` does.
+
+This is deliberately lenient: it skips to the first run of digits for the location and the next run of
+digits for the length.  Anything missing is 0, and values that don't fit are clamped to `NSIntegerMax`.
+*/
+#[allow(non_snake_case)]
+pub fn NSRangeFromString(string: &str) -> NSRange {
+    fn scan(bytes: &[u8]) -> Option<(NSUInteger, &[u8])> {
+        let start = bytes.iter().position(|b| b.is_ascii_digit())?;
+        let digits = bytes[start..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let mut value: NSUInteger = 0;
+        for b in &bytes[start..start + digits] {
+            value = value.saturating_mul(10).saturating_add((b - b'0') as NSUInteger).min(NSInteger::MAX as NSUInteger);
+        }
+        Some((value, &bytes[start + digits..]))
+    }
+    let mut range = NSRange::default();
+    if let Some((location, rest)) = scan(string.as_bytes()) {
+        range.location = location;
+        if let Some((length, _)) = scan(rest) {
+            range.length = length;
+        }
+    }
+    range
+}
+
+///An empty Rust range (start >= end) becomes a zero-length `NSRange` at `start`.
+impl From<Range<NSUInteger>> for NSRange {
+    fn from(range: Range<NSUInteger>) -> Self {
+        NSRange { location: range.start, length: range.end.saturating_sub(range.start) }
+    }
+}
+///# Panics
+/// If `location + length` overflows.
+impl From<NSRange> for Range<NSUInteger> {
+    fn from(range: NSRange) -> Self {
+        range.location..NSMaxRange(range)
+    }
+}
+
+///Errors translating between UTF-8 byte indices and UTF-16 code-unit indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Utf16IndexError {
+    ///The index is past the end of the string.
+    OutOfBounds { index: usize, len: usize },
+    ///The byte index falls inside a multi-byte UTF-8 sequence.
+    NotCharBoundary(usize),
+    ///The UTF-16 index falls between the halves of a surrogate pair.
+    SplitsSurrogatePair(usize),
+    ///The range's start is after its end, or its end overflows.
+    InvalidRange,
+}
+impl Display for Utf16IndexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Utf16IndexError::OutOfBounds { index, len } => write!(f, "index {} out of bounds for length {}", index, len),
+            Utf16IndexError::NotCharBoundary(index) => write!(f, "byte index {} is not a char boundary", index),
+            Utf16IndexError::SplitsSurrogatePair(index) => write!(f, "UTF-16 index {} splits a surrogate pair", index),
+            Utf16IndexError::InvalidRange => write!(f, "invalid range"),
+        }
+    }
+}
+impl std::error::Error for Utf16IndexError {}
+
+#[derive(Debug, Clone, Copy)]
+struct WideChar {
+    byte: usize,
+    utf16: usize,
+    byte_len: usize,
+    utf16_len: usize,
+}
+
+/**
+Translates between UTF-8 byte indices into a Rust `str` and UTF-16 code-unit indices into the
+equivalent `NSString`.
+
+Building the map walks the string once and records only the non-ASCII characters, so
+lookups are a binary search and ASCII-only strings cost nothing.
+
+Indices are checked at the scalar-value level: any boundary that is a char boundary in Rust
+(including every grapheme cluster boundary) translates exactly, and an index that splits
+a code point is an error.  Grapheme segmentation itself is left to the caller; translate the byte
+ranges it produces.
+*/
+#[derive(Debug, Clone)]
+pub struct Utf16IndexMap<'a> {
+    str: &'a str,
+    wide: Vec<WideChar>,
+    utf16_len: usize,
+}
+
+impl<'a> Utf16IndexMap<'a> {
+    pub fn new(str: &'a str) -> Self {
+        let mut wide = Vec::new();
+        let mut utf16 = 0;
+        for (byte, char) in str.char_indices() {
+            let utf16_len = char.len_utf16();
+            if !char.is_ascii() {
+                wide.push(WideChar { byte, utf16, byte_len: char.len_utf8(), utf16_len });
+            }
+            utf16 += utf16_len;
+        }
+        Self { str, wide, utf16_len: utf16 }
+    }
+    pub fn as_str(&self) -> &'a str {
+        self.str
+    }
+    ///Length in UTF-8 bytes.
+    pub fn len(&self) -> usize {
+        self.str.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.str.is_empty()
+    }
+    ///Length in UTF-16 code units, which is `-[NSString length]`.
+    pub fn utf16_len(&self) -> usize {
+        self.utf16_len
+    }
+
+    pub fn byte_to_utf16(&self, byte: usize) -> Result<usize, Utf16IndexError> {
+        if byte > self.str.len() {
+            return Err(Utf16IndexError::OutOfBounds { index: byte, len: self.str.len() })
+        }
+        //last wide char starting at or before `byte`
+        let idx = self.wide.partition_point(|w| w.byte <= byte);
+        if idx == 0 {
+            return Ok(byte)
+        }
+        let w = self.wide[idx - 1];
+        if byte == w.byte {
+            Ok(w.utf16)
+        }
+        else if byte < w.byte + w.byte_len {
+            Err(Utf16IndexError::NotCharBoundary(byte))
+        }
+        else {
+            Ok(w.utf16 + w.utf16_len + (byte - w.byte - w.byte_len))
+        }
+    }
+
+    pub fn utf16_to_byte(&self, utf16: usize) -> Result<usize, Utf16IndexError> {
+        if utf16 > self.utf16_len {
+            return Err(Utf16IndexError::OutOfBounds { index: utf16, len: self.utf16_len })
+        }
+        let idx = self.wide.partition_point(|w| w.utf16 <= utf16);
+        if idx == 0 {
+            return Ok(utf16)
+        }
+        let w = self.wide[idx - 1];
+        if utf16 == w.utf16 {
+            Ok(w.byte)
+        }
+        else if utf16 < w.utf16 + w.utf16_len {
+            Err(Utf16IndexError::SplitsSurrogatePair(utf16))
+        }
+        else {
+            Ok(w.byte + w.byte_len + (utf16 - w.utf16 - w.utf16_len))
+        }
+    }
+
+    ///Converts a byte range of the string into the equivalent `NSRange`.
+    pub fn to_nsrange(&self, range: Range<usize>) -> Result<NSRange, Utf16IndexError> {
+        if range.start > range.end {
+            return Err(Utf16IndexError::InvalidRange)
+        }
+        let start = self.byte_to_utf16(range.start)?;
+        let end = self.byte_to_utf16(range.end)?;
+        Ok(NSRange { location: start as NSUInteger, length: (end - start) as NSUInteger })
+    }
+
+    ///Converts an `NSRange` of the equivalent `NSString` into a byte range of the string.
+    pub fn to_byte_range(&self, range: NSRange) -> Result<Range<usize>, Utf16IndexError> {
+        let start = range.location as usize;
+        let end = start.checked_add(range.length as usize).ok_or(Utf16IndexError::InvalidRange)?;
+        Ok(self.utf16_to_byte(start)?..self.utf16_to_byte(end)?)
+    }
+}
+
+#[test] fn utf16_index_map_offsets() {
+    let map = Utf16IndexMap::new("aé😀b");
+    assert_eq!(map.utf16_len(), 5);
+    assert_eq!(map.byte_to_utf16(0), Ok(0));
+    assert_eq!(map.byte_to_utf16(1), Ok(1));
+    assert_eq!(map.byte_to_utf16(2), Err(Utf16IndexError::NotCharBoundary(2)));
+    assert_eq!(map.byte_to_utf16(3), Ok(2));
+    assert_eq!(map.byte_to_utf16(7), Ok(4));
+    assert_eq!(map.byte_to_utf16(8), Ok(5));
+    assert_eq!(map.byte_to_utf16(9), Err(Utf16IndexError::OutOfBounds { index: 9, len: 8 }));
+
+    assert_eq!(map.utf16_to_byte(2), Ok(3));
+    assert_eq!(map.utf16_to_byte(3), Err(Utf16IndexError::SplitsSurrogatePair(3)));
+    assert_eq!(map.utf16_to_byte(4), Ok(7));
+    assert_eq!(map.utf16_to_byte(5), Ok(8));
+    assert_eq!(map.utf16_to_byte(6), Err(Utf16IndexError::OutOfBounds { index: 6, len: 5 }));
+    assert_eq!(Utf16IndexMap::new("").utf16_to_byte(0), Ok(0));
+}
+
+#[test] fn utf16_index_map_exhaustive() {
+    let strings = [
+        "",
+        "ascii only",
+        "é",
+        "aé😀b",
+        "😀😀",
+        "日本語テキスト",
+        //combining sequence and a ZWJ family; both are multi-scalar grapheme clusters
+        "e\u{301}x👨\u{200d}👩\u{200d}👧!",
+        "\u{10ffff}a\u{7f}\u{80}\u{7ff}\u{800}\u{ffff}\u{10000}",
+    ];
+    for str in strings {
+        let map = Utf16IndexMap::new(str);
+        let units: Vec<u16> = str.encode_utf16().collect();
+        assert_eq!(map.utf16_len(), units.len());
+
+        //brute-force expectations: every (byte, utf16) pair at a char boundary
+        let mut boundaries = Vec::new();
+        let mut utf16 = 0;
+        for (byte, char) in str.char_indices() {
+            boundaries.push((byte, utf16));
+            utf16 += char.len_utf16();
+        }
+        boundaries.push((str.len(), utf16));
+
+        for byte in 0..=str.len() + 1 {
+            let expected = match boundaries.iter().find(|b| b.0 == byte) {
+                Some(b) => Ok(b.1),
+                None if byte > str.len() => Err(Utf16IndexError::OutOfBounds { index: byte, len: str.len() }),
+                None => Err(Utf16IndexError::NotCharBoundary(byte)),
+            };
+            assert_eq!(map.byte_to_utf16(byte), expected, "{:?} byte {}", str, byte);
+        }
+        for utf16 in 0..=units.len() + 1 {
+            let expected = match boundaries.iter().find(|b| b.1 == utf16) {
+                Some(b) => Ok(b.0),
+                None if utf16 > units.len() => Err(Utf16IndexError::OutOfBounds { index: utf16, len: units.len() }),
+                None => Err(Utf16IndexError::SplitsSurrogatePair(utf16)),
+            };
+            assert_eq!(map.utf16_to_byte(utf16), expected, "{:?} utf16 {}", str, utf16);
+        }
+
+        //every boundary range round-trips and agrees with encode_utf16
+        for &(start_byte, start_utf16) in &boundaries {
+            for &(end_byte, end_utf16) in boundaries.iter().filter(|b| b.0 >= start_byte) {
+                let range = map.to_nsrange(start_byte..end_byte).unwrap();
+                assert_eq!(range.location as usize, start_utf16);
+                assert_eq!(range.length as usize, end_utf16 - start_utf16);
+                let expected: Vec<u16> = str[start_byte..end_byte].encode_utf16().collect();
+                assert_eq!(&units[start_utf16..end_utf16], &expected[..]);
+                assert_eq!(map.to_byte_range(range).unwrap(), start_byte..end_byte);
+            }
+        }
+    }
+}
+
+#[test] fn utf16_index_map_errors() {
+    let map = Utf16IndexMap::new("a😀");
+    #[allow(clippy::reversed_empty_ranges)]
+    let inverted = 1..0;
+    assert_eq!(map.to_nsrange(inverted).unwrap_err(), Utf16IndexError::InvalidRange);
+    assert_eq!(map.to_nsrange(0..2).unwrap_err(), Utf16IndexError::NotCharBoundary(2));
+    assert_eq!(map.to_byte_range(NSRange { location: 0, length: 2 }), Err(Utf16IndexError::SplitsSurrogatePair(2)));
+    assert_eq!(map.to_byte_range(NSRange { location: 1, length: NSUInteger::MAX }), Err(Utf16IndexError::InvalidRange));
+    assert_eq!(map.to_byte_range(NSRange { location: 0, length: 4 }), Err(Utf16IndexError::OutOfBounds { index: 4, len: 3 }));
+
+    //a grapheme cluster's byte range maps to the whole cluster in UTF-16
+    let str = "xe\u{301}y";
+    let map = Utf16IndexMap::new(str);
+    let range = map.to_nsrange(1..4).unwrap();
+    assert_eq!((range.location, range.length), (1, 2));
+}
+
+#[test] fn range_functions() {
+    let a = NSRange { location: 2, length: 5 };
+    let b = NSRange { location: 4, length: 10 };
+    assert_eq!(NSMaxRange(a), 7);
+    assert_eq!(NSUnionRange(a, b), NSRange { location: 2, length: 12 });
+    assert_eq!(NSIntersectionRange(a, b), NSRange { location: 4, length: 3 });
+    assert_eq!(a.intersection(NSRange { location: 20, length: 1 }), NSRange::default());
+    //touching ranges intersect in an empty range at the boundary
+    assert_eq!(a.intersection(NSRange { location: 7, length: 1 }), NSRange { location: 7, length: 0 });
+    assert!(NSLocationInRange(2, a));
+    assert!(a.contains(6));
+    assert!(!a.contains(7));
+    assert!(!a.contains(1));
+    assert!(!NSRange { location: 3, length: 0 }.contains(3));
+}
+
+#[test] fn range_construction() {
+    assert_eq!(NSRange::new(1, 2), Some(NSRange { location: 1, length: 2 }));
+    assert_eq!(NSRange::new(NSUInteger::MAX, 1), None);
+    assert_eq!(NSRange { location: NSUInteger::MAX, length: 1 }.checked_max(), None);
+
+    assert_eq!(NSRange::from(3..8), NSRange { location: 3, length: 5 });
+    #[allow(clippy::reversed_empty_ranges)]
+    let inverted = 8..3;
+    assert_eq!(NSRange::from(inverted), NSRange { location: 8, length: 0 });
+    let range: Range<NSUInteger> = NSRange { location: 3, length: 5 }.into();
+    assert_eq!(range, 3..8);
+
+    assert!(NSRange::NOT_FOUND.is_not_found());
+    assert_eq!(NSRange::NOT_FOUND.found(), None);
+    assert_eq!(NSRange { location: 0, length: 1 }.found(), Some(NSRange { location: 0, length: 1 }));
+    assert_eq!(NSNotFound, 0x7fff_ffff_ffff_ffff);
+
+    let mut set = std::collections::HashSet::new();
+    set.insert(NSRange { location: 1, length: 1 });
+    assert!(set.contains(&NSRange { location: 1, length: 1 }));
+}
+
+#[test] fn range_strings() {
+    let range = NSRange { location: 3, length: 42 };
+    assert_eq!(NSStringFromRange(range), "{3, 42}");
+    assert_eq!(NSRangeFromString(&NSStringFromRange(range)), range);
+    assert_eq!(NSRangeFromString("3 42"), range);
+    assert_eq!(NSRangeFromString("location: 3; length: 42; trailing 9"), range);
+    assert_eq!(NSRangeFromString("{3}"), NSRange { location: 3, length: 0 });
+    assert_eq!(NSRangeFromString(""), NSRange::default());
+    assert_eq!(NSRangeFromString("{}"), NSRange::default());
+    assert_eq!(NSRangeFromString("{99999999999999999999999, 1}"), NSRange { location: NSInteger::MAX as NSUInteger, length: 1 });
+}
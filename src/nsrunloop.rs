@@ -28,7 +28,7 @@ impl NSRunLoop {
 #[test] fn runloop() {
     autoreleasepool(|pool| {
         let runloop = NSRunLoop::mainRunLoop(pool);
-        let date = NSDate::class().alloc_init(pool);
+        let date = NSDate::dateWithTimeIntervalSinceNow(0.01, pool);
         runloop.runUntilDate(&date, pool);
     })
 }
\ No newline at end of file
@@ -1,15 +1,194 @@
 use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
 use objr::bindings::*;
 use crate::NSUInteger;
+#[cfg(feature="nsrange")]
+use crate::NSRange;
 
 objc_selector_group! {
     trait NSStringSelectors {
         @selector("initWithBytesNoCopy:length:encoding:freeWhenDone:")
-
+        @selector("initWithBytes:length:encoding:")
+        @selector("initWithCharacters:length:")
+        @selector("length")
+        @selector("lengthOfBytesUsingEncoding:")
+        @selector("getCharacters:range:")
+        @selector("getBytes:maxLength:usedLength:encoding:options:range:remainingRange:")
     }
     impl NSStringSelectors for Sel {}
 }
 
+/**
+`NSStringEncoding`.
+
+Foundation passes these around as a bare `NSUInteger`; use [NSStringEncoding::raw] and
+`TryFrom<NSUInteger>` to convert.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NSStringEncoding {
+    ASCII,
+    NEXTSTEP,
+    JapaneseEUC,
+    UTF8,
+    ISOLatin1,
+    Symbol,
+    NonLossyASCII,
+    ShiftJIS,
+    ISOLatin2,
+    ///`NSUnicodeStringEncoding`, host byte order with a BOM when externalized
+    UTF16,
+    WindowsCP1251,
+    WindowsCP1252,
+    WindowsCP1253,
+    WindowsCP1254,
+    WindowsCP1250,
+    ISO2022JP,
+    MacOSRoman,
+    UTF16BigEndian,
+    UTF16LittleEndian,
+    UTF32,
+    UTF32BigEndian,
+    UTF32LittleEndian,
+}
+
+impl NSStringEncoding {
+    pub const fn raw(self) -> NSUInteger {
+        match self {
+            NSStringEncoding::ASCII => 1,
+            NSStringEncoding::NEXTSTEP => 2,
+            NSStringEncoding::JapaneseEUC => 3,
+            NSStringEncoding::UTF8 => 4,
+            NSStringEncoding::ISOLatin1 => 5,
+            NSStringEncoding::Symbol => 6,
+            NSStringEncoding::NonLossyASCII => 7,
+            NSStringEncoding::ShiftJIS => 8,
+            NSStringEncoding::ISOLatin2 => 9,
+            NSStringEncoding::UTF16 => 10,
+            NSStringEncoding::WindowsCP1251 => 11,
+            NSStringEncoding::WindowsCP1252 => 12,
+            NSStringEncoding::WindowsCP1253 => 13,
+            NSStringEncoding::WindowsCP1254 => 14,
+            NSStringEncoding::WindowsCP1250 => 15,
+            NSStringEncoding::ISO2022JP => 21,
+            NSStringEncoding::MacOSRoman => 30,
+            NSStringEncoding::UTF16BigEndian => 0x9000_0100,
+            NSStringEncoding::UTF16LittleEndian => 0x9400_0100,
+            NSStringEncoding::UTF32 => 0x8c00_0100,
+            NSStringEncoding::UTF32BigEndian => 0x9800_0100,
+            NSStringEncoding::UTF32LittleEndian => 0x9c00_0100,
+        }
+    }
+}
+
+impl TryFrom<NSUInteger> for NSStringEncoding {
+    type Error = NSStringError;
+    fn try_from(raw: NSUInteger) -> Result<Self, Self::Error> {
+        Ok(match raw {
+            1 => NSStringEncoding::ASCII,
+            2 => NSStringEncoding::NEXTSTEP,
+            3 => NSStringEncoding::JapaneseEUC,
+            4 => NSStringEncoding::UTF8,
+            5 => NSStringEncoding::ISOLatin1,
+            6 => NSStringEncoding::Symbol,
+            7 => NSStringEncoding::NonLossyASCII,
+            8 => NSStringEncoding::ShiftJIS,
+            9 => NSStringEncoding::ISOLatin2,
+            10 => NSStringEncoding::UTF16,
+            11 => NSStringEncoding::WindowsCP1251,
+            12 => NSStringEncoding::WindowsCP1252,
+            13 => NSStringEncoding::WindowsCP1253,
+            14 => NSStringEncoding::WindowsCP1254,
+            15 => NSStringEncoding::WindowsCP1250,
+            21 => NSStringEncoding::ISO2022JP,
+            30 => NSStringEncoding::MacOSRoman,
+            0x9000_0100 => NSStringEncoding::UTF16BigEndian,
+            0x9400_0100 => NSStringEncoding::UTF16LittleEndian,
+            0x8c00_0100 => NSStringEncoding::UTF32,
+            0x9800_0100 => NSStringEncoding::UTF32BigEndian,
+            0x9c00_0100 => NSStringEncoding::UTF32LittleEndian,
+            other => return Err(NSStringError::UnknownEncoding(other)),
+        })
+    }
+}
+
+/**
+`NSStringEncodingConversionOptions`
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSStringEncodingConversionOptions(pub NSUInteger);
+impl NSStringEncodingConversionOptions {
+    pub const ALLOW_LOSSY: Self = Self(1);
+    pub const EXTERNAL_REPRESENTATION: Self = Self(2);
+}
+impl std::ops::BitOr for NSStringEncodingConversionOptions {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/**
+`NSStringCompareOptions`
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NSStringCompareOptions(pub NSUInteger);
+impl NSStringCompareOptions {
+    pub const CASE_INSENSITIVE: Self = Self(1);
+    pub const LITERAL: Self = Self(2);
+    pub const BACKWARDS: Self = Self(4);
+    pub const ANCHORED: Self = Self(8);
+    pub const NUMERIC: Self = Self(64);
+    pub const DIACRITIC_INSENSITIVE: Self = Self(128);
+    pub const WIDTH_INSENSITIVE: Self = Self(256);
+    pub const FORCED_ORDERING: Self = Self(512);
+    pub const REGULAR_EXPRESSION: Self = Self(1024);
+}
+impl std::ops::BitOr for NSStringCompareOptions {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+///Errors converting between Rust strings, bytes, and `NSString`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NSStringError {
+    ///The `NSUInteger` is not a known [NSStringEncoding].
+    UnknownEncoding(NSUInteger),
+    ///Foundation rejected the bytes as invalid for the encoding.
+    InvalidBytes(NSStringEncoding),
+    ///The string contains characters that can't be represented in the encoding.
+    Unrepresentable(NSStringEncoding),
+}
+impl Display for NSStringError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NSStringError::UnknownEncoding(raw) => write!(f, "Unknown NSStringEncoding {}", raw),
+            NSStringError::InvalidBytes(encoding) => write!(f, "Bytes are not valid {:?}", encoding),
+            NSStringError::Unrepresentable(encoding) => write!(f, "String can't be represented as {:?}", encoding),
+        }
+    }
+}
+impl std::error::Error for NSStringError {}
+
+///Result of [NSStringExtension::getBytes].
+#[cfg(feature="nsrange")]
+#[derive(Debug)]
+pub struct GetBytesResult {
+    ///Whether any characters were converted.
+    pub converted: bool,
+    ///Number of bytes written into the buffer.
+    pub used_length: NSUInteger,
+    ///The part of the requested range that didn't fit (or couldn't be converted).
+    pub remaining: NSRange,
+}
+
+/**
+Conversions between Rust strings and `NSString`.
+
+These are extension methods rather than `From` impls because every conversion needs an autorelease pool,
+and neither `String` nor `NSString` is local to this crate.
+*/
 pub trait NSStringExtension {
     ///This function creates a string that borrows the argument.
     ///
@@ -26,42 +205,166 @@ pub trait NSStringExtension {
     In Rust, the string is valid for the lifetime of the argument.  'By convention', most objc cod
     that encounters the NSString will end up copying it, which will copy the data into a new objec
     with extended lifetime, although this is not guaranteed
+
+    # Panics
+    If the argument is not valid UTF-8.  See [Self::try_from_borrowed_os_str].
       */
     fn from_borrowed_os_str<'a>(str: &'a OsStr, pool: &ActiveAutoreleasePool) -> StrongLifetimeCell<'a, NSString>;
+    ///Like [Self::from_borrowed_os_str], but returns an error if the argument is not valid UTF-8.
+    fn try_from_borrowed_os_str<'a>(str: &'a OsStr, pool: &ActiveAutoreleasePool) -> Result<StrongLifetimeCell<'a, NSString>, NSStringError>;
+    ///Borrows `bytes` in the specified encoding.  See [Self::from_borrowed_str] for lifetime caveats.
+    fn from_borrowed_bytes<'a>(bytes: &'a [u8], encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> Result<StrongLifetimeCell<'a, NSString>, NSStringError>;
+    ///Copies `bytes` in the specified encoding into a new string.
+    ///
+    /// ```objc
+    /// - (nullable instancetype)initWithBytes:(const void *)bytes length:(NSUInteger)len encoding:(NSStringEncoding)encoding;
+    /// ```
+    fn from_bytes(bytes: &[u8], encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSString>, NSStringError>;
+    ///Copies UTF-16 code units into a new string.  This is lossless, unpaired surrogates are preserved.
+    fn from_utf16(units: &[u16], pool: &ActiveAutoreleasePool) -> StrongCell<NSString>;
+
+    ///Length in UTF-16 code units.
+    fn length(&self, pool: &ActiveAutoreleasePool) -> NSUInteger;
+    ///Number of bytes required to store the string in `encoding`, or 0 if it can't be converted.
+    #[allow(non_snake_case)]
+    fn lengthOfBytesUsingEncoding(&self, encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> NSUInteger;
+    ///Copies the UTF-16 code units of the string.  This is lossless, unpaired surrogates are preserved.
+    #[cfg(feature="nsrange")]
+    fn to_utf16(&self, pool: &ActiveAutoreleasePool) -> Vec<u16>;
+    ///```objc
+    /// - (BOOL)getBytes:(nullable void *)buffer maxLength:(NSUInteger)maxBufferCount usedLength:(nullable NSUInteger *)usedBufferCount encoding:(NSStringEncoding)encoding options:(NSStringEncodingConversionOptions)options range:(NSRange)range remainingRange:(nullable NSRangePointer)leftover;
+    /// ```
+    #[cfg(feature="nsrange")]
+    #[allow(non_snake_case)]
+    fn getBytes(&self, buffer: &mut [u8], encoding: NSStringEncoding, options: NSStringEncodingConversionOptions, range: NSRange, pool: &ActiveAutoreleasePool) -> GetBytesResult;
+    ///Converts the entire string into `encoding`, failing if any character can't be represented.
+    #[cfg(feature="nsrange")]
+    fn to_bytes(&self, encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> Result<Vec<u8>, NSStringError>;
+    ///Copies the string into a Rust `String`.
+    ///
+    /// Fails if the string contains unpaired surrogates, which have no UTF-8 representation.
+    #[cfg(feature="nsrange")]
+    fn try_to_string(&self, pool: &ActiveAutoreleasePool) -> Result<String, NSStringError>;
 }
 impl NSStringExtension for NSString {
 
     fn from_borrowed_str<'a>(str: &'a str, pool: &ActiveAutoreleasePool) -> StrongLifetimeCell<'a,NSString> {
-        let bytes = str.as_bytes().as_ptr();
-        let len = str.len() as NSUInteger;
-        unsafe {
-            let alloc = Self::class().alloc(pool);
-
-            let encoding: NSUInteger = 4;
-            let raw = Self::perform(alloc, Sel::initWithBytesNoCopy_length_encoding_freeWhenDone(), &pool, (bytes.assume_nonmut_perform(), len,encoding,false ));
-            //we assume this will work since it's str already
-            Self::assume_nonnil(raw).assume_retained_limited()
-        }
+        //str is already UTF-8
+        Self::from_borrowed_bytes(str.as_bytes(), NSStringEncoding::UTF8, pool).unwrap()
     }
 
     fn from_borrowed_os_str<'a>(str: &'a OsStr, pool: &ActiveAutoreleasePool) -> StrongLifetimeCell<'a, NSString> {
+        Self::try_from_borrowed_os_str(str, pool).unwrap()
+    }
+
+    fn try_from_borrowed_os_str<'a>(str: &'a OsStr, pool: &ActiveAutoreleasePool) -> Result<StrongLifetimeCell<'a, NSString>, NSStringError> {
         use std::os::unix::ffi::OsStrExt;
-        let bytes = str.as_bytes();
+        Self::from_borrowed_bytes(str.as_bytes(), NSStringEncoding::UTF8, pool)
+    }
+
+    fn from_borrowed_bytes<'a>(bytes: &'a [u8], encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> Result<StrongLifetimeCell<'a, NSString>, NSStringError> {
         let len = bytes.len() as NSUInteger;
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithBytesNoCopy_length_encoding_freeWhenDone(), &pool, (bytes.as_ptr().assume_nonmut_perform(), len,encoding.raw(),false ));
+            Self::nullable(raw).map(|r| r.assume_retained_limited()).ok_or(NSStringError::InvalidBytes(encoding))
+        }
+    }
+
+    fn from_bytes(bytes: &[u8], encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> Result<StrongCell<NSString>, NSStringError> {
+        unsafe {
+            let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithBytes_length_encoding(), pool, (bytes.as_ptr().assume_nonmut_perform(), bytes.len() as NSUInteger, encoding.raw()));
+            Self::nullable(raw).assume_retained().ok_or(NSStringError::InvalidBytes(encoding))
+        }
+    }
 
-        let encoding: NSUInteger = 4;
+    fn from_utf16(units: &[u16], pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
         unsafe {
             let alloc = Self::class().alloc(pool);
+            let raw = Self::perform(alloc, Sel::initWithCharacters_length(), pool, (units.as_ptr().assume_nonmut_perform(), units.len() as NSUInteger));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+
+    fn length(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::length(), pool, ())
+        }
+    }
 
-            let raw = Self::perform(alloc, Sel::initWithBytesNoCopy_length_encoding_freeWhenDone(), &pool, (bytes.as_ptr().assume_nonmut_perform(), len,encoding,false ));
-            //we assume this will work since it's str already
-            Self::assume_nonnil(raw).assume_retained_limited()
+    fn lengthOfBytesUsingEncoding(&self, encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> NSUInteger {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::lengthOfBytesUsingEncoding_(), pool, (encoding.raw(),))
         }
+    }
 
+    #[cfg(feature="nsrange")]
+    fn to_utf16(&self, pool: &ActiveAutoreleasePool) -> Vec<u16> {
+        let length = self.length(pool);
+        let mut units: Vec<u16> = Vec::with_capacity(length as usize);
+        unsafe {
+            let _: () = Self::perform_primitive(self.assume_nonmut_perform(), Sel::getCharacters_range(), pool, (units.as_mut_ptr(), NSRange { location: 0, length }));
+            //safety: getCharacters:range: wrote `length` units
+            units.set_len(length as usize);
+        }
+        units
+    }
+
+    #[cfg(feature="nsrange")]
+    fn getBytes(&self, buffer: &mut [u8], encoding: NSStringEncoding, options: NSStringEncodingConversionOptions, range: NSRange, pool: &ActiveAutoreleasePool) -> GetBytesResult {
+        let mut used_length: NSUInteger = 0;
+        let mut remaining = NSRange { location: 0, length: 0 };
+        let converted = unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::getBytes_maxLength_usedLength_encoding_options_range_remainingRange(), pool, (
+                buffer.as_mut_ptr(), buffer.len() as NSUInteger, &mut used_length as *mut NSUInteger, encoding.raw(), options.0, range, &mut remaining as *mut NSRange
+            ))
+        };
+        GetBytesResult { converted, used_length, remaining }
+    }
+
+    #[cfg(feature="nsrange")]
+    fn to_bytes(&self, encoding: NSStringEncoding, pool: &ActiveAutoreleasePool) -> Result<Vec<u8>, NSStringError> {
+        let length = self.length(pool);
+        if length == 0 {
+            return Ok(Vec::new())
+        }
+        let byte_length = self.lengthOfBytesUsingEncoding(encoding, pool);
+        if byte_length == 0 {
+            return Err(NSStringError::Unrepresentable(encoding))
+        }
+        let mut bytes = vec![0; byte_length as usize];
+        let result = self.getBytes(&mut bytes, encoding, NSStringEncodingConversionOptions::default(), NSRange { location: 0, length }, pool);
+        if !result.converted || result.remaining.length != 0 {
+            return Err(NSStringError::Unrepresentable(encoding))
+        }
+        bytes.truncate(result.used_length as usize);
+        Ok(bytes)
+    }
+
+    #[cfg(feature="nsrange")]
+    fn try_to_string(&self, pool: &ActiveAutoreleasePool) -> Result<String, NSStringError> {
+        let bytes = self.to_bytes(NSStringEncoding::UTF8, pool)?;
+        String::from_utf8(bytes).map_err(|_| NSStringError::Unrepresentable(NSStringEncoding::UTF8))
     }
 
 }
 
+#[test] fn encoding_raw() {
+    let all = [
+        NSStringEncoding::ASCII, NSStringEncoding::NEXTSTEP, NSStringEncoding::JapaneseEUC, NSStringEncoding::UTF8,
+        NSStringEncoding::ISOLatin1, NSStringEncoding::Symbol, NSStringEncoding::NonLossyASCII, NSStringEncoding::ShiftJIS,
+        NSStringEncoding::ISOLatin2, NSStringEncoding::UTF16, NSStringEncoding::WindowsCP1251, NSStringEncoding::WindowsCP1252,
+        NSStringEncoding::WindowsCP1253, NSStringEncoding::WindowsCP1254, NSStringEncoding::WindowsCP1250, NSStringEncoding::ISO2022JP,
+        NSStringEncoding::MacOSRoman, NSStringEncoding::UTF16BigEndian, NSStringEncoding::UTF16LittleEndian, NSStringEncoding::UTF32,
+        NSStringEncoding::UTF32BigEndian, NSStringEncoding::UTF32LittleEndian,
+    ];
+    for encoding in all {
+        assert_eq!(NSStringEncoding::try_from(encoding.raw()), Ok(encoding));
+    }
+    assert_eq!(NSStringEncoding::UTF8.raw(), 4);
+    assert_eq!(NSStringEncoding::try_from(0), Err(NSStringError::UnknownEncoding(0)));
+}
 
 #[test] fn borrow() {
     let pool = unsafe{ AutoreleasePool::new()};
@@ -86,6 +389,34 @@ impl NSStringExtension for NSString {
     autoreleasepool(|pool| {
         let nsstring = NSString::from_borrowed_os_str(os_str,pool);
         let description = nsstring.description(pool).to_string();
-        assert_eq!(&description, "test my på†h")
+        assert_eq!(&description, "test my på†h");
+
+        use std::os::unix::ffi::OsStrExt;
+        let invalid = OsStr::from_bytes(&[0x66, 0x6f, 0x80]);
+        assert!(NSString::try_from_borrowed_os_str(invalid, pool).is_err());
     });
-}
\ No newline at end of file
+}
+
+#[cfg(feature="nsrange")]
+#[test] fn conversions() {
+    autoreleasepool(|pool| {
+        let string = NSString::from_bytes("päth 😀".as_bytes(), NSStringEncoding::UTF8, pool).unwrap();
+        assert_eq!(string.length(pool), 7);
+        assert_eq!(string.try_to_string(pool).unwrap(), "päth 😀");
+        assert_eq!(string.to_bytes(NSStringEncoding::UTF16LittleEndian, pool).unwrap().len(), 14);
+        assert_eq!(string.to_bytes(NSStringEncoding::ASCII, pool), Err(NSStringError::Unrepresentable(NSStringEncoding::ASCII)));
+        assert_eq!(string.to_bytes(NSStringEncoding::ISOLatin1, pool), Err(NSStringError::Unrepresentable(NSStringEncoding::ISOLatin1)));
+
+        let latin = NSString::from_bytes(&[0x70, 0xe4], NSStringEncoding::ISOLatin1, pool).unwrap();
+        assert_eq!(latin.try_to_string(pool).unwrap(), "pä");
+        assert_eq!(latin.lengthOfBytesUsingEncoding(NSStringEncoding::MacOSRoman, pool), 2);
+
+        //lone surrogate round-trips through UTF-16 but not UTF-8
+        let units = [0x61, 0xd800, 0x62];
+        let lone = NSString::from_utf16(&units, pool);
+        assert_eq!(lone.to_utf16(pool), units);
+        assert!(lone.try_to_string(pool).is_err());
+
+        assert!(NSString::from_bytes(&[0xff, 0xfe, 0xfd], NSStringEncoding::UTF8, pool).is_err());
+    })
+}
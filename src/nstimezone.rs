@@ -0,0 +1,114 @@
+use objr::bindings::*;
+use crate::{NSDate, NSInteger};
+
+objc_class! {
+    pub struct NSTimeZone {
+        @class(NSTimeZone)
+    }
+}
+objc_selector_group! {
+    trait Selectors {
+        @selector("timeZoneWithName:")
+        @selector("timeZoneForSecondsFromGMT:")
+        @selector("name")
+        @selector("systemTimeZone")
+        @selector("localTimeZone")
+        @selector("secondsFromGMT")
+        @selector("secondsFromGMTForDate:")
+        @selector("abbreviation")
+        @selector("isDaylightSavingTimeForDate:")
+    }
+    impl Selectors for Sel {}
+}
+
+//immutable
+unsafe impl Send for NSTimeZone {}
+unsafe impl Sync for NSTimeZone {}
+
+#[allow(non_snake_case)]
+impl NSTimeZone {
+    ///A time zone by its tz database name (such as `"America/Chicago"`) or abbreviation (such as `"GMT"`), or `None` if the name is unknown.
+    pub fn timeZoneWithName(name: &NSString, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Self>> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::timeZoneWithName_(), pool, (name.assume_nonmut_perform(),));
+            Self::nullable(raw).assume_retained()
+        }
+    }
+    ///A fixed offset from GMT, or `None` if the offset is out of range (more than 18 hours).
+    pub fn timeZoneForSecondsFromGMT(seconds: NSInteger, pool: &ActiveAutoreleasePool) -> Option<StrongCell<Self>> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::timeZoneForSecondsFromGMT_(), pool, (seconds,));
+            Self::nullable(raw).assume_retained()
+        }
+    }
+    ///GMT.
+    pub fn gmt(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        Self::timeZoneForSecondsFromGMT(0, pool).unwrap()
+    }
+    ///The time zone the system is set to.  Foundation caches this; see `NSTimeZone.resetSystemTimeZone`.
+    pub fn systemTimeZone(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::systemTimeZone(), pool, ());
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///A time zone that tracks the app's default time zone, even if it changes later.
+    pub fn localTimeZone(pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::localTimeZone(), pool, ());
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///The identifier, such as `"America/Chicago"`.
+    pub fn name(&self, pool: &ActiveAutoreleasePool) -> StrongCell<NSString> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::name(), pool, ());
+            NSString::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///The current offset from GMT.
+    pub fn secondsFromGMT(&self, pool: &ActiveAutoreleasePool) -> NSInteger {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::secondsFromGMT(), pool, ())
+        }
+    }
+    pub fn secondsFromGMTForDate(&self, date: &NSDate, pool: &ActiveAutoreleasePool) -> NSInteger {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::secondsFromGMTForDate_(), pool, (date.assume_nonmut_perform(),))
+        }
+    }
+    ///The current abbreviation, such as `"CST"`, if there is one.
+    pub fn abbreviation(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<NSString>> {
+        unsafe {
+            let raw = Self::perform_autorelease_to_retain(self.assume_nonmut_perform(), Sel::abbreviation(), pool, ());
+            NSString::nullable(raw).assume_retained()
+        }
+    }
+    pub fn isDaylightSavingTimeForDate(&self, date: &NSDate, pool: &ActiveAutoreleasePool) -> bool {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::isDaylightSavingTimeForDate_(), pool, (date.assume_nonmut_perform(),))
+        }
+    }
+}
+
+#[test] fn time_zones() {
+    autoreleasepool(|pool| {
+        let chicago = NSTimeZone::timeZoneWithName(objc_nsstring!("America/Chicago"), pool).unwrap();
+        assert_eq!(chicago.name(pool).to_str(pool), "America/Chicago");
+        assert!(NSTimeZone::timeZoneWithName(objc_nsstring!("Not/AZone"), pool).is_none());
+        assert_eq!(NSTimeZone::gmt(pool).name(pool).to_str(pool), "GMT");
+
+        let winter = NSDate::dateWithTimeIntervalSince1970(1704067200.0, pool); //2024-01-01
+        let summer = NSDate::dateWithTimeIntervalSince1970(1719792000.0, pool); //2024-07-01
+        assert_eq!(chicago.secondsFromGMTForDate(&winter, pool), -6 * 3600);
+        assert_eq!(chicago.secondsFromGMTForDate(&summer, pool), -5 * 3600);
+        assert!(chicago.isDaylightSavingTimeForDate(&summer, pool));
+        assert!(!chicago.isDaylightSavingTimeForDate(&winter, pool));
+
+        let fixed = NSTimeZone::timeZoneForSecondsFromGMT(5400, pool).unwrap();
+        assert_eq!(fixed.secondsFromGMT(pool), 5400);
+        assert!(NSTimeZone::timeZoneForSecondsFromGMT(100 * 3600, pool).is_none());
+        NSTimeZone::systemTimeZone(pool).name(pool);
+        NSTimeZone::localTimeZone(pool).abbreviation(pool);
+    })
+}
@@ -1,7 +1,8 @@
 
 use objr::bindings::*;
-use std::os::raw::{c_int, c_ulong};
-use crate::NSUInteger;
+use std::ffi::{c_void, CStr, CString};
+use std::os::raw::{c_char, c_double, c_float, c_int, c_long, c_longlong, c_short, c_uchar, c_uint, c_ulong, c_ulonglong, c_ushort};
+use crate::{NSComparisonResult, NSInteger, NSUInteger, NSPoint, NSRect, TypeEncoding};
 objc_class! {
     pub struct NSNumber {
         @class(NSNumber)
@@ -10,42 +11,402 @@ objc_class! {
 
 objc_selector_group! {
     trait NSNumberSelectors {
+        @selector("initWithChar:")
+        @selector("initWithUnsignedChar:")
+        @selector("initWithShort:")
+        @selector("initWithUnsignedShort:")
         @selector("initWithInt:")
+        @selector("initWithUnsignedInt:")
+        @selector("initWithLong:")
         @selector("initWithUnsignedLong:")
-        @selector("unsignedIntegerValue")
+        @selector("initWithLongLong:")
+        @selector("initWithUnsignedLongLong:")
+        @selector("initWithFloat:")
+        @selector("initWithDouble:")
         @selector("initWithBool:")
+        @selector("initWithInteger:")
+        @selector("initWithUnsignedInteger:")
+        @selector("charValue")
+        @selector("unsignedCharValue")
+        @selector("shortValue")
+        @selector("unsignedShortValue")
+        @selector("intValue")
+        @selector("unsignedIntValue")
+        @selector("longValue")
+        @selector("unsignedLongValue")
+        @selector("longLongValue")
+        @selector("unsignedLongLongValue")
+        @selector("floatValue")
+        @selector("doubleValue")
+        @selector("boolValue")
+        @selector("integerValue")
+        @selector("unsignedIntegerValue")
+        @selector("objCType")
+        @selector("compare:")
+        @selector("isEqualToNumber:")
     }
     impl NSNumberSelectors for Sel {}
 }
 
+#[link(name="CoreFoundation", kind="framework")]
+extern "C" {
+    //toll-free bridged with the NSNumber singletons for YES and NO
+    static kCFBooleanTrue: *const c_void;
+    static kCFBooleanFalse: *const c_void;
+}
+
+///The scalar type an [NSNumber] reports from `objCType`.
+///
+/// Foundation normalizes what it stores, so this may not be the type the number was created with.  For example,
+/// `with_ushort` typically reports [NSNumberObjCType::Int], and `with_bool` reports [NSNumberObjCType::Char].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NSNumberObjCType {
+    Char,
+    UnsignedChar,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+    Float,
+    Double,
+    Bool,
+    ///Some other type encoding.
+    Unknown(u8),
+}
+impl NSNumberObjCType {
+    ///Parses an `@encode` string, such as `"q"`.
+    pub fn from_encoding(encoding: &[u8]) -> Self {
+        match encoding {
+            b"c" => Self::Char,
+            b"C" => Self::UnsignedChar,
+            b"s" => Self::Short,
+            b"S" => Self::UnsignedShort,
+            b"i" => Self::Int,
+            b"I" => Self::UnsignedInt,
+            b"l" => Self::Long,
+            b"L" => Self::UnsignedLong,
+            b"q" => Self::LongLong,
+            b"Q" => Self::UnsignedLongLong,
+            b"f" => Self::Float,
+            b"d" => Self::Double,
+            b"B" => Self::Bool,
+            other => Self::Unknown(other.first().copied().unwrap_or(0)),
+        }
+    }
+    pub fn encoding(self) -> u8 {
+        match self {
+            Self::Char => b'c',
+            Self::UnsignedChar => b'C',
+            Self::Short => b's',
+            Self::UnsignedShort => b'S',
+            Self::Int => b'i',
+            Self::UnsignedInt => b'I',
+            Self::Long => b'l',
+            Self::UnsignedLong => b'L',
+            Self::LongLong => b'q',
+            Self::UnsignedLongLong => b'Q',
+            Self::Float => b'f',
+            Self::Double => b'd',
+            Self::Bool => b'B',
+            Self::Unknown(c) => c,
+        }
+    }
+    pub fn is_signed_integer(self) -> bool {
+        matches!(self, Self::Char | Self::Short | Self::Int | Self::Long | Self::LongLong)
+    }
+    pub fn is_unsigned_integer(self) -> bool {
+        matches!(self, Self::UnsignedChar | Self::UnsignedShort | Self::UnsignedInt | Self::UnsignedLong | Self::UnsignedLongLong)
+    }
+}
+
+/**
+The value stored in an [NSNumber], in the widest Rust type that holds it exactly.
+
+Use this to encode numbers (for example, to JSON or a plist) without coercing integers to floats or booleans to integers.
+Foundation stores unsigned values that fit in an `i64` as signed, so those come back as [NumberValue::I64].
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberValue {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+}
+
+macro_rules! number_init {
+    ($name:ident, $sel:ident, $ty:ty) => {
+        pub fn $name(v: $ty, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+            unsafe {
+                let s = Self::class().alloc(pool);
+                let s = Self::perform(s,Sel::$sel(), pool, (v,));
+                Self::assume_nonnil(s).assume_retained()
+            }
+        }
+    }
+}
+macro_rules! number_value {
+    ($name:ident, $ty:ty) => {
+        pub fn $name(&self, pool: &ActiveAutoreleasePool) -> $ty {
+            unsafe {
+                Self::perform_primitive(self.assume_nonmut_perform(), Sel::$name(), pool, ())
+            }
+        }
+    }
+}
+
+/**
+`NSNumber`.
+
+The getters convert like the C casts Foundation documents: they may truncate or lose precision.  To get the stored
+value exactly, use [NSNumber::value].
+*/
 #[allow(non_snake_case)]
 impl NSNumber {
-    pub fn with_int(int: c_int, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+    number_init!(with_char, initWithChar_, c_char);
+    number_init!(with_uchar, initWithUnsignedChar_, c_uchar);
+    number_init!(with_short, initWithShort_, c_short);
+    number_init!(with_ushort, initWithUnsignedShort_, c_ushort);
+    number_init!(with_int, initWithInt_, c_int);
+    number_init!(with_uint, initWithUnsignedInt_, c_uint);
+    number_init!(with_long, initWithLong_, c_long);
+    number_init!(with_ulong, initWithUnsignedLong_, c_ulong);
+    number_init!(with_longlong, initWithLongLong_, c_longlong);
+    number_init!(with_ulonglong, initWithUnsignedLongLong_, c_ulonglong);
+    number_init!(with_float, initWithFloat_, c_float);
+    number_init!(with_double, initWithDouble_, c_double);
+    number_init!(with_bool, initWithBool_, bool);
+    number_init!(with_integer, initWithInteger_, NSInteger);
+    number_init!(with_uinteger, initWithUnsignedInteger_, NSUInteger);
+
+    ///Creates a number that reports the same [NumberValue].
+    pub fn with_value(value: NumberValue, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        match value {
+            NumberValue::Bool(b) => Self::with_bool(b, pool),
+            NumberValue::I64(i) => Self::with_longlong(i, pool),
+            NumberValue::U64(u) => Self::with_ulonglong(u, pool),
+            NumberValue::F32(f) => Self::with_float(f, pool),
+            NumberValue::F64(d) => Self::with_double(d, pool),
+        }
+    }
+
+    number_value!(charValue, c_char);
+    number_value!(unsignedCharValue, c_uchar);
+    number_value!(shortValue, c_short);
+    number_value!(unsignedShortValue, c_ushort);
+    number_value!(intValue, c_int);
+    number_value!(unsignedIntValue, c_uint);
+    number_value!(longValue, c_long);
+    number_value!(unsignedLongValue, c_ulong);
+    number_value!(longLongValue, c_longlong);
+    number_value!(unsignedLongLongValue, c_ulonglong);
+    number_value!(floatValue, c_float);
+    number_value!(doubleValue, c_double);
+    number_value!(boolValue, bool);
+    number_value!(integerValue, NSInteger);
+    number_value!(unsignedIntegerValue, NSUInteger);
+
+    pub fn objCType(&self, pool: &ActiveAutoreleasePool) -> NSNumberObjCType {
+        unsafe {
+            let ptr: *const c_char = Self::perform_primitive(self.assume_nonmut_perform(), Sel::objCType(), pool, ());
+            NSNumberObjCType::from_encoding(CStr::from_ptr(ptr).to_bytes())
+        }
+    }
+    ///Whether this is one of the boolean singletons created by [Self::with_bool] (or `@YES`/`@NO`).
+    ///
+    /// `objCType` can't tell, because booleans report [NSNumberObjCType::Char].
+    pub fn is_bool(&self) -> bool {
+        let ptr = self as *const Self as *const c_void;
+        unsafe { ptr == kCFBooleanTrue || ptr == kCFBooleanFalse }
+    }
+    ///The stored value, without lossy conversion.
+    pub fn value(&self, pool: &ActiveAutoreleasePool) -> NumberValue {
+        if self.is_bool() {
+            return NumberValue::Bool(self.boolValue(pool))
+        }
+        let ty = self.objCType(pool);
+        if ty.is_signed_integer() {
+            NumberValue::I64(self.longLongValue(pool))
+        }
+        else if ty.is_unsigned_integer() {
+            NumberValue::U64(self.unsignedLongLongValue(pool))
+        }
+        else if ty == NSNumberObjCType::Float {
+            NumberValue::F32(self.floatValue(pool))
+        }
+        else {
+            NumberValue::F64(self.doubleValue(pool))
+        }
+    }
+    pub fn compare(&self, other: &NSNumber, pool: &ActiveAutoreleasePool) -> NSComparisonResult {
+        unsafe {
+            NSComparisonResult::from_raw(Self::perform_primitive(self.assume_nonmut_perform(), Sel::compare_(), pool, (other.assume_nonmut_perform(),)))
+        }
+    }
+    pub fn isEqualToNumber(&self, other: &NSNumber, pool: &ActiveAutoreleasePool) -> bool {
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::isEqualToNumber_(), pool, (other.assume_nonmut_perform(),))
+        }
+    }
+}
+
+objc_class! {
+    pub struct NSValue {
+        @class(NSValue)
+    }
+}
+
+objc_selector_group! {
+    trait NSValueSelectors {
+        @selector("valueWithRange:")
+        @selector("rangeValue")
+        @selector("valueWithPoint:")
+        @selector("pointValue")
+        @selector("valueWithRect:")
+        @selector("rectValue")
+        @selector("valueWithPointer:")
+        @selector("pointerValue")
+        @selector("valueWithNonretainedObject:")
+        @selector("nonretainedObjectValue")
+        @selector("valueWithBytes:objCType:")
+        @selector("getValue:size:")
+        @selector("objCType")
+    }
+    impl NSValueSelectors for Sel {}
+}
+
+/**
+`NSValue`.
+
+To box your own `#[repr(C)]` types, implement [TypeEncoding] and use [NSValue::with_value] and [NSValue::value].
+*/
+#[allow(non_snake_case)]
+impl NSValue {
+    #[cfg(feature="nsrange")]
+    pub fn valueWithRange(range: crate::NSRange, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::valueWithRange_(), pool, (range,));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///# Panics
+    /// If the receiver doesn't hold an `NSRange`.  Use [Self::value] to check instead.
+    #[cfg(feature="nsrange")]
+    pub fn rangeValue(&self, pool: &ActiveAutoreleasePool) -> crate::NSRange {
+        self.assert_holds::<crate::NSRange>(pool);
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::rangeValue(), pool, ())
+        }
+    }
+    pub fn valueWithPoint(point: NSPoint, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::valueWithPoint_(), pool, (point,));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///# Panics
+    /// If the receiver doesn't hold an `NSPoint`.
+    pub fn pointValue(&self, pool: &ActiveAutoreleasePool) -> NSPoint {
+        self.assert_holds::<NSPoint>(pool);
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::pointValue(), pool, ())
+        }
+    }
+    pub fn valueWithRect(rect: NSRect, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::valueWithRect_(), pool, (rect,));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    ///# Panics
+    /// If the receiver doesn't hold an `NSRect`.
+    pub fn rectValue(&self, pool: &ActiveAutoreleasePool) -> NSRect {
+        self.assert_holds::<NSRect>(pool);
+        unsafe {
+            Self::perform_primitive(self.assume_nonmut_perform(), Sel::rectValue(), pool, ())
+        }
+    }
+    ///Boxes the pointer itself.  The pointee is not retained or copied.
+    pub fn valueWithPointer(pointer: *const c_void, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        unsafe {
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::valueWithPointer_(), pool, (pointer.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
+        }
+    }
+    pub fn pointerValue(&self, pool: &ActiveAutoreleasePool) -> *const c_void {
+        unsafe {
+            let ptr: *mut c_void = Self::perform_primitive(self.assume_nonmut_perform(), Sel::pointerValue(), pool, ());
+            ptr
+        }
+    }
+    ///Boxes `object` without retaining it.
+    pub fn valueWithNonretainedObject<T: ObjcInstance>(object: &T, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
         unsafe {
-            let s = Self::class().alloc(pool);
-            let s = Self::perform(s,Sel::initWithInt_(), pool, (int,));
-            Self::assume_nonnil(s).assume_retained()
+            let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::valueWithNonretainedObject_(), pool, (object.assume_nonmut_perform(),));
+            Self::assume_nonnil(raw).assume_retained()
         }
     }
-    pub fn with_ulong(v: c_ulong, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+    ///# Safety
+    /// The object passed to [Self::valueWithNonretainedObject] must still be alive, and must be a `T`.
+    pub unsafe fn nonretainedObjectValue<T: ObjcInstance>(&self, pool: &ActiveAutoreleasePool) -> Option<StrongCell<T>> {
+        let raw: *const T = Self::perform_primitive(self.assume_nonmut_perform(), Sel::nonretainedObjectValue(), pool, ());
+        if raw.is_null() { None } else { Some((*raw).retain()) }
+    }
+    /**
+    `valueWithBytes:objCType:`
+
+    # Safety
+    `bytes` must point to a value whose layout is described exactly by `objc_type`, because Foundation copies
+    as many bytes as the encoding calls for.  [Self::with_value] checks this through [TypeEncoding].
+    */
+    pub unsafe fn valueWithBytesObjCType(bytes: *const c_void, objc_type: &CStr, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        let raw = Class::perform_autorelease_to_retain(Self::class().assume_nonmut_perform(), Sel::valueWithBytes_objCType(), pool, (bytes.assume_nonmut_perform(), objc_type.as_ptr().assume_nonmut_perform()));
+        Self::assume_nonnil(raw).assume_retained()
+    }
+    ///`valueWithBytes:objCType:` with the encoding from [TypeEncoding].
+    pub fn with_value<T: TypeEncoding + Copy>(value: &T, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+        let encoding = CString::new(T::encoding()).unwrap();
         unsafe {
-            let s = Self::class().alloc(pool);
-            let s = Self::perform(s,Sel::initWithUnsignedLong_(), pool, (v,));
-            Self::assume_nonnil(s).assume_retained()
+            Self::valueWithBytesObjCType(value as *const T as *const c_void, &encoding, pool)
         }
     }
-    pub fn with_bool(b: bool, pool: &ActiveAutoreleasePool) -> StrongCell<Self> {
+    ///The raw `@encode` string of the boxed value.
+    pub fn objCType(&self, pool: &ActiveAutoreleasePool) -> &CStr {
         unsafe {
-            let s = Self::class().alloc(pool);
-            let s = Self::perform(s,Sel::initWithBool_(), pool, (b,));
-            Self::assume_nonnil(s).assume_retained()
+            let ptr: *const c_char = Self::perform_primitive(self.assume_nonmut_perform(), Sel::objCType(), pool, ());
+            CStr::from_ptr(ptr)
         }
     }
-    pub fn unsignedIntegerValue(&self, pool: &ActiveAutoreleasePool) -> NSUInteger {
+    /**
+    `getValue:size:`
+
+    # Safety
+    `buffer` must be valid for writes of `size` bytes, and the boxed value must be `size` bytes (Foundation raises otherwise).
+    Interpreting the bytes is up to the caller; [Self::value] checks this through [TypeEncoding].
+    */
+    pub unsafe fn getValueSize(&self, buffer: *mut c_void, size: NSUInteger, pool: &ActiveAutoreleasePool) {
+        let _: () = Self::perform_primitive(self.assume_nonmut_perform(), Sel::getValue_size(), pool, (buffer, size));
+    }
+    ///Unboxes the value with `getValue:size:`, or returns `None` if the receiver doesn't hold a `T`.
+    pub fn value<T: TypeEncoding + Copy>(&self, pool: &ActiveAutoreleasePool) -> Option<T> {
+        if self.objCType(pool).to_bytes() != T::encoding().as_bytes() {
+            return None
+        }
+        let mut value = std::mem::MaybeUninit::<T>::uninit();
         unsafe {
-            Self::perform_primitive(self.assume_nonmut_perform(), Sel::unsignedIntegerValue(), pool, ())
+            self.getValueSize(value.as_mut_ptr() as *mut c_void, std::mem::size_of::<T>() as NSUInteger, pool);
+            Some(value.assume_init())
         }
     }
+    fn assert_holds<T: TypeEncoding>(&self, pool: &ActiveAutoreleasePool) {
+        let encoding = T::encoding();
+        let actual = self.objCType(pool);
+        assert!(actual.to_bytes() == encoding.as_bytes(), "NSValue holds {:?}, not {}", actual, encoding);
+    }
 }
 
 #[test] fn test() {
@@ -60,3 +421,81 @@ impl NSNumber {
         assert_eq!(s.unsignedIntegerValue(pool), 1);
     })
 }
+
+#[test] fn scalars() {
+    autoreleasepool(|pool| {
+        assert_eq!(NSNumber::with_char(-3, pool).charValue(pool), -3);
+        assert_eq!(NSNumber::with_uchar(250, pool).unsignedCharValue(pool), 250);
+        assert_eq!(NSNumber::with_short(-300, pool).shortValue(pool), -300);
+        assert_eq!(NSNumber::with_ushort(60000, pool).unsignedShortValue(pool), 60000);
+        assert_eq!(NSNumber::with_uint(4_000_000_000, pool).unsignedIntValue(pool), 4_000_000_000);
+        assert_eq!(NSNumber::with_long(-5, pool).longValue(pool), -5);
+        assert_eq!(NSNumber::with_longlong(i64::MIN, pool).longLongValue(pool), i64::MIN);
+        assert_eq!(NSNumber::with_ulonglong(u64::MAX, pool).unsignedLongLongValue(pool), u64::MAX);
+        assert_eq!(NSNumber::with_float(1.5, pool).floatValue(pool), 1.5);
+        assert_eq!(NSNumber::with_double(0.1, pool).doubleValue(pool), 0.1);
+        assert_eq!(NSNumber::with_integer(-7, pool).integerValue(pool), -7);
+        assert!(NSNumber::with_bool(true, pool).boolValue(pool));
+
+        assert_eq!(NSNumber::with_double(2.0, pool).objCType(pool), NSNumberObjCType::Double);
+        assert_eq!(NSNumber::with_longlong(2, pool).objCType(pool), NSNumberObjCType::LongLong);
+        assert_eq!(NSNumberObjCType::from_encoding(b"{CGPoint=dd}"), NSNumberObjCType::Unknown(b'{'));
+        assert_eq!(NSNumberObjCType::from_encoding(b"Q").encoding(), b'Q');
+    })
+}
+
+#[test] fn values() {
+    autoreleasepool(|pool| {
+        for value in [NumberValue::Bool(false), NumberValue::Bool(true), NumberValue::I64(i64::MIN), NumberValue::I64(-1),
+                      NumberValue::U64(u64::MAX), NumberValue::F32(0.5), NumberValue::F64(0.1)] {
+            assert_eq!(NSNumber::with_value(value, pool).value(pool), value);
+        }
+        assert_eq!(NSNumber::with_char(1, pool).value(pool), NumberValue::I64(1));
+        assert!(!NSNumber::with_int(1, pool).is_bool());
+
+        let one = NSNumber::with_int(1, pool);
+        let two = NSNumber::with_double(2.0, pool);
+        assert_eq!(one.compare(&two, pool), NSComparisonResult::OrderedAscending);
+        assert_eq!(two.compare(&one, pool), NSComparisonResult::OrderedDescending);
+        assert!(one.isEqualToNumber(&NSNumber::with_double(1.0, pool), pool));
+    })
+}
+
+#[test] fn values_boxing() {
+    use coregraphicsr::{CGPoint, CGRect, CGSize};
+    autoreleasepool(|pool| {
+        let point = CGPoint { x: 1.0, y: 2.0 };
+        let value = NSValue::valueWithPoint(point, pool);
+        assert_eq!(value.pointValue(pool).y, 2.0);
+        assert!(value.value::<CGRect>(pool).is_none());
+
+        let rect = CGRect { origin: point, size: CGSize { width: 3.0, height: 4.0 } };
+        let value = NSValue::valueWithRect(rect, pool);
+        assert_eq!(value.rectValue(pool).size.height, 4.0);
+        assert_eq!(value.value::<CGRect>(pool).unwrap().size.width, 3.0);
+
+        #[cfg(feature="nsrange")] {
+            let range = crate::NSRange { location: 2, length: 3 };
+            let value = NSValue::valueWithRange(range, pool);
+            assert_eq!(value.objCType(pool).to_bytes(), b"{_NSRange=QQ}");
+            assert_eq!(value.rangeValue(pool), range);
+            assert_eq!(NSValue::with_value(&range, pool).value::<crate::NSRange>(pool), Some(range));
+        }
+
+        let x = 5u8;
+        let value = NSValue::valueWithPointer(&x as *const u8 as *const c_void, pool);
+        assert_eq!(value.pointerValue(pool), &x as *const u8 as *const c_void);
+
+        let string = objc_nsstring!("not retained");
+        let value = NSValue::valueWithNonretainedObject(string, pool);
+        let back: StrongCell<NSString> = unsafe { value.nonretainedObjectValue(pool) }.unwrap();
+        assert_eq!(back.to_str(pool), "not retained");
+    })
+}
+
+#[test] #[should_panic] fn wrong_unbox() {
+    autoreleasepool(|pool| {
+        let value = NSValue::valueWithPoint(coregraphicsr::CGPoint { x: 0.0, y: 0.0 }, pool);
+        value.rectValue(pool);
+    })
+}
@@ -0,0 +1,502 @@
+/*!
+ObjC type encodings, as produced by `@encode`.
+
+[TypeEncoding] produces encodings for Rust types, and [Encoding::parse] parses encoding strings, such as those
+returned by `NSValue.objCType` or the runtime's method type strings.
+*/
+use std::fmt::{Display, Formatter};
+use std::os::raw::c_void;
+use coregraphicsr::{CGPoint, CGRect, CGSize};
+
+/**
+Types with a known ObjC type encoding.
+
+# Safety
+The encoding must describe the type's layout exactly, because Foundation reads and writes values of the type based on it
+(for example, in `NSValue`).  In practice the type must be `#[repr(C)]` (or a primitive) with fields encoded in order.
+*/
+pub unsafe trait TypeEncoding {
+    ///Appends the encoding to `out`.
+    fn encode(out: &mut String);
+    ///The encoding, for example `{_NSRange=QQ}`.
+    fn encoding() -> String {
+        let mut out = String::new();
+        Self::encode(&mut out);
+        out
+    }
+    ///The parsed encoding.
+    fn type_encoding() -> Encoding {
+        Encoding::parse(&Self::encoding()).expect("TypeEncoding produced an invalid encoding")
+    }
+}
+
+macro_rules! scalar_encoding {
+    ($($ty:ty => $encoding:literal),* $(,)?) => {
+        $(
+        unsafe impl TypeEncoding for $ty {
+            fn encode(out: &mut String) {
+                out.push_str($encoding)
+            }
+        }
+        )*
+    }
+}
+scalar_encoding! {
+    i8 => "c",
+    u8 => "C",
+    i16 => "s",
+    u16 => "S",
+    i32 => "i",
+    u32 => "I",
+    i64 => "q",
+    u64 => "Q",
+    f32 => "f",
+    f64 => "d",
+    bool => "B",
+    () => "v",
+    c_void => "v",
+}
+#[cfg(target_pointer_width = "64")]
+scalar_encoding! {
+    isize => "q",
+    usize => "Q",
+}
+unsafe impl<T: TypeEncoding> TypeEncoding for *const T {
+    fn encode(out: &mut String) {
+        out.push('^');
+        T::encode(out)
+    }
+}
+unsafe impl<T: TypeEncoding> TypeEncoding for *mut T {
+    fn encode(out: &mut String) {
+        out.push('^');
+        T::encode(out)
+    }
+}
+unsafe impl<T: TypeEncoding, const N: usize> TypeEncoding for [T; N] {
+    fn encode(out: &mut String) {
+        out.push('[');
+        out.push_str(&N.to_string());
+        T::encode(out);
+        out.push(']');
+    }
+}
+
+///Appends a struct encoding such as `{name=...}` with each field's encoding in order.
+pub fn encode_struct(out: &mut String, name: &str, fields: &[fn(&mut String)]) {
+    out.push('{');
+    out.push_str(name);
+    out.push('=');
+    for field in fields {
+        field(out);
+    }
+    out.push('}');
+}
+
+unsafe impl TypeEncoding for CGPoint {
+    fn encode(out: &mut String) {
+        encode_struct(out, "CGPoint", &[f64::encode, f64::encode])
+    }
+}
+unsafe impl TypeEncoding for CGSize {
+    fn encode(out: &mut String) {
+        encode_struct(out, "CGSize", &[f64::encode, f64::encode])
+    }
+}
+unsafe impl TypeEncoding for CGRect {
+    fn encode(out: &mut String) {
+        encode_struct(out, "CGRect", &[CGPoint::encode, CGSize::encode])
+    }
+}
+#[cfg(feature="nsrange")]
+unsafe impl TypeEncoding for crate::NSRange {
+    fn encode(out: &mut String) {
+        encode_struct(out, "_NSRange", &[u64::encode, u64::encode])
+    }
+}
+
+/**
+A parsed type encoding.
+
+[Display] writes the encoding back out, so parsing and displaying round-trips (except that field names and
+the offsets in method signatures are dropped).
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Char,
+    UnsignedChar,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+    Int128,
+    UnsignedInt128,
+    Float,
+    Double,
+    LongDouble,
+    Bool,
+    Void,
+    ///`char *`
+    CString,
+    ///`id`, with the class name if the encoding includes it (as in `@"NSString"`).
+    Object(Option<String>),
+    ///A block, encoded as `@?`.
+    Block,
+    ///`Class`
+    Class,
+    ///`SEL`
+    Selector,
+    ///`?`, such as a function pointer.
+    Unknown,
+    ///A bitfield of the given width.
+    BitField(u32),
+    Pointer(Box<Encoding>),
+    Array(u64, Box<Encoding>),
+    ///`{name=fields}`.  `fields` is `None` when the encoding omits them, as in `{name}` or behind some pointers.
+    Struct(String, Option<Vec<Encoding>>),
+    ///`(name=fields)`
+    Union(String, Option<Vec<Encoding>>),
+    ///A type with a method qualifier such as `r` (`const`) or `o` (`out`).
+    Qualified(char, Box<Encoding>),
+}
+
+///Errors from [Encoding::parse].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodingParseError {
+    ///The encoding ended in the middle of a type.
+    UnexpectedEnd,
+    ///An unexpected character at the given byte offset.
+    UnexpectedCharacter(usize, char),
+    ///A complete type was parsed, but more characters follow at the given byte offset.
+    TrailingCharacters(usize),
+    ///An array length or bitfield width at the given byte offset is missing or too large.
+    InvalidNumber(usize),
+    ///The type at the given byte offset is nested more than 128 levels deep.
+    TooDeeplyNested(usize),
+}
+impl Display for EncodingParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodingParseError::UnexpectedEnd => write!(f, "Encoding ended unexpectedly"),
+            EncodingParseError::UnexpectedCharacter(offset, c) => write!(f, "Unexpected {:?} at offset {}", c, offset),
+            EncodingParseError::TrailingCharacters(offset) => write!(f, "Unexpected characters after the type at offset {}", offset),
+            EncodingParseError::InvalidNumber(offset) => write!(f, "Invalid number at offset {}", offset),
+            EncodingParseError::TooDeeplyNested(offset) => write!(f, "Type at offset {} is nested too deeply", offset),
+        }
+    }
+}
+impl std::error::Error for EncodingParseError {}
+
+///How deeply pointers, arrays, structs, unions and qualifiers may nest before parsing fails, so the parser can't overflow the stack.
+const MAX_NESTING: usize = 128;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).copied()
+    }
+    fn next(&mut self) -> Result<u8, EncodingParseError> {
+        let b = self.peek().ok_or(EncodingParseError::UnexpectedEnd)?;
+        self.offset += 1;
+        Ok(b)
+    }
+    fn expect(&mut self, expected: u8) -> Result<(), EncodingParseError> {
+        let offset = self.offset;
+        match self.next()? {
+            b if b == expected => Ok(()),
+            b => Err(EncodingParseError::UnexpectedCharacter(offset, b as char)),
+        }
+    }
+    fn number(&mut self) -> Result<u64, EncodingParseError> {
+        let start = self.offset;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.offset += 1;
+        }
+        //digits are ASCII, so this slice is on char boundaries
+        std::str::from_utf8(&self.bytes[start..self.offset]).unwrap().parse().map_err(|_| EncodingParseError::InvalidNumber(start))
+    }
+    ///Reads up to (but not including) one of `terminators`.
+    fn name(&mut self, terminators: &[u8]) -> Result<String, EncodingParseError> {
+        let start = self.offset;
+        loop {
+            match self.peek() {
+                None => return Err(EncodingParseError::UnexpectedEnd),
+                Some(b) if terminators.contains(&b) => break,
+                Some(_) => self.offset += 1,
+            }
+        }
+        Ok(String::from_utf8_lossy(&self.bytes[start..self.offset]).into_owned())
+    }
+    ///Skips a quoted field name, as in `{CGPoint="x"d"y"d}`.
+    fn skip_field_name(&mut self) -> Result<(), EncodingParseError> {
+        if self.peek() == Some(b'"') {
+            self.offset += 1;
+            self.name(b"\"")?;
+            self.offset += 1;
+        }
+        Ok(())
+    }
+    fn aggregate(&mut self, close: u8) -> Result<(String, Option<Vec<Encoding>>), EncodingParseError> {
+        let name = self.name(&[b'=', close])?;
+        if self.next()? == close {
+            return Ok((name, None))
+        }
+        let mut fields = Vec::new();
+        loop {
+            self.skip_field_name()?;
+            if self.peek() == Some(close) {
+                self.offset += 1;
+                return Ok((name, Some(fields)))
+            }
+            fields.push(self.parse_type()?);
+        }
+    }
+    fn parse_type(&mut self) -> Result<Encoding, EncodingParseError> {
+        let offset = self.offset;
+        if self.depth == MAX_NESTING {
+            return Err(EncodingParseError::TooDeeplyNested(offset))
+        }
+        self.depth += 1;
+        let encoding = match self.next()? {
+            b'c' => Encoding::Char,
+            b'C' => Encoding::UnsignedChar,
+            b's' => Encoding::Short,
+            b'S' => Encoding::UnsignedShort,
+            b'i' => Encoding::Int,
+            b'I' => Encoding::UnsignedInt,
+            b'l' => Encoding::Long,
+            b'L' => Encoding::UnsignedLong,
+            b'q' => Encoding::LongLong,
+            b'Q' => Encoding::UnsignedLongLong,
+            b't' => Encoding::Int128,
+            b'T' => Encoding::UnsignedInt128,
+            b'f' => Encoding::Float,
+            b'd' => Encoding::Double,
+            b'D' => Encoding::LongDouble,
+            b'B' => Encoding::Bool,
+            b'v' => Encoding::Void,
+            b'*' => Encoding::CString,
+            b'#' => Encoding::Class,
+            b':' => Encoding::Selector,
+            b'?' => Encoding::Unknown,
+            b'@' => match self.peek() {
+                Some(b'?') => {
+                    self.offset += 1;
+                    Encoding::Block
+                }
+                Some(b'"') => {
+                    self.offset += 1;
+                    let name = self.name(b"\"")?;
+                    self.offset += 1;
+                    Encoding::Object(Some(name))
+                }
+                _ => Encoding::Object(None)
+            },
+            b'b' => {
+                let width = self.number()?;
+                Encoding::BitField(width.try_into().map_err(|_| EncodingParseError::InvalidNumber(offset + 1))?)
+            }
+            b'^' => Encoding::Pointer(Box::new(self.parse_type()?)),
+            b'[' => {
+                let length = self.number()?;
+                let element = self.parse_type()?;
+                self.expect(b']')?;
+                Encoding::Array(length, Box::new(element))
+            }
+            b'{' => {
+                let (name, fields) = self.aggregate(b'}')?;
+                Encoding::Struct(name, fields)
+            }
+            b'(' => {
+                let (name, fields) = self.aggregate(b')')?;
+                Encoding::Union(name, fields)
+            }
+            q @ (b'r' | b'n' | b'N' | b'o' | b'O' | b'R' | b'V' | b'A' | b'j') => Encoding::Qualified(q as char, Box::new(self.parse_type()?)),
+            _ => {
+                //report the whole char, not just its first byte
+                let c = std::str::from_utf8(&self.bytes[offset..]).ok().and_then(|s| s.chars().next()).unwrap_or('\u{FFFD}');
+                return Err(EncodingParseError::UnexpectedCharacter(offset, c))
+            }
+        };
+        self.depth -= 1;
+        Ok(encoding)
+    }
+}
+
+impl Encoding {
+    ///Parses a single type, such as `{_NSRange=QQ}`.
+    pub fn parse(encoding: &str) -> Result<Encoding, EncodingParseError> {
+        let mut parser = Parser { bytes: encoding.as_bytes(), offset: 0, depth: 0 };
+        let parsed = parser.parse_type()?;
+        if parser.offset != parser.bytes.len() {
+            return Err(EncodingParseError::TrailingCharacters(parser.offset))
+        }
+        Ok(parsed)
+    }
+    /**
+    Parses a method signature, such as `v24@0:8@16`, into the return type followed by the argument types
+    (including the receiver and selector).  The stack offsets are skipped.
+    */
+    pub fn parse_method_signature(signature: &str) -> Result<Vec<Encoding>, EncodingParseError> {
+        let mut parser = Parser { bytes: signature.as_bytes(), offset: 0, depth: 0 };
+        let mut types = Vec::new();
+        while parser.peek().is_some() {
+            types.push(parser.parse_type()?);
+            //offsets may be negative on some platforms
+            if parser.peek() == Some(b'-') {
+                parser.offset += 1;
+            }
+            while matches!(parser.peek(), Some(b'0'..=b'9')) {
+                parser.offset += 1;
+            }
+        }
+        if types.is_empty() {
+            return Err(EncodingParseError::UnexpectedEnd)
+        }
+        Ok(types)
+    }
+    ///Whether `T`'s [TypeEncoding] is this encoding.
+    pub fn is<T: TypeEncoding>(&self) -> bool {
+        *self == T::type_encoding()
+    }
+    ///Removes any method qualifiers, which don't affect layout.
+    pub fn unqualified(&self) -> &Encoding {
+        match self {
+            Encoding::Qualified(_, inner) => inner.unqualified(),
+            other => other
+        }
+    }
+}
+
+fn write_aggregate(f: &mut Formatter<'_>, open: char, close: char, name: &str, fields: &Option<Vec<Encoding>>) -> std::fmt::Result {
+    write!(f, "{}{}", open, name)?;
+    if let Some(fields) = fields {
+        write!(f, "=")?;
+        for field in fields {
+            write!(f, "{}", field)?;
+        }
+    }
+    write!(f, "{}", close)
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Encoding::Char => write!(f, "c"),
+            Encoding::UnsignedChar => write!(f, "C"),
+            Encoding::Short => write!(f, "s"),
+            Encoding::UnsignedShort => write!(f, "S"),
+            Encoding::Int => write!(f, "i"),
+            Encoding::UnsignedInt => write!(f, "I"),
+            Encoding::Long => write!(f, "l"),
+            Encoding::UnsignedLong => write!(f, "L"),
+            Encoding::LongLong => write!(f, "q"),
+            Encoding::UnsignedLongLong => write!(f, "Q"),
+            Encoding::Int128 => write!(f, "t"),
+            Encoding::UnsignedInt128 => write!(f, "T"),
+            Encoding::Float => write!(f, "f"),
+            Encoding::Double => write!(f, "d"),
+            Encoding::LongDouble => write!(f, "D"),
+            Encoding::Bool => write!(f, "B"),
+            Encoding::Void => write!(f, "v"),
+            Encoding::CString => write!(f, "*"),
+            Encoding::Object(None) => write!(f, "@"),
+            Encoding::Object(Some(class)) => write!(f, "@\"{}\"", class),
+            Encoding::Block => write!(f, "@?"),
+            Encoding::Class => write!(f, "#"),
+            Encoding::Selector => write!(f, ":"),
+            Encoding::Unknown => write!(f, "?"),
+            Encoding::BitField(width) => write!(f, "b{}", width),
+            Encoding::Pointer(inner) => write!(f, "^{}", inner),
+            Encoding::Array(length, element) => write!(f, "[{}{}]", length, element),
+            Encoding::Struct(name, fields) => write_aggregate(f, '{', '}', name, fields),
+            Encoding::Union(name, fields) => write_aggregate(f, '(', ')', name, fields),
+            Encoding::Qualified(qualifier, inner) => write!(f, "{}{}", qualifier, inner),
+        }
+    }
+}
+
+#[test] fn encodings() {
+    assert_eq!(u64::encoding(), "Q");
+    assert_eq!(<*const c_void>::encoding(), "^v");
+    assert_eq!(CGRect::encoding(), "{CGRect={CGPoint=dd}{CGSize=dd}}");
+    #[cfg(feature="nsrange")]
+    assert_eq!(crate::NSRange::encoding(), "{_NSRange=QQ}");
+}
+
+#[test] fn generic_encodings() {
+    assert_eq!(<*const CGPoint>::encoding(), "^{CGPoint=dd}");
+    assert_eq!(<*mut *const u8>::encoding(), "^^C");
+    assert_eq!(<[u16; 4]>::encoding(), "[4S]");
+    assert!(CGRect::type_encoding().is::<CGRect>());
+    assert!(!CGRect::type_encoding().is::<CGPoint>());
+}
+
+#[test] fn parse_scalars() {
+    for (string, encoding) in [("c", Encoding::Char), ("Q", Encoding::UnsignedLongLong), ("d", Encoding::Double), ("B", Encoding::Bool),
+                               ("v", Encoding::Void), ("*", Encoding::CString), ("#", Encoding::Class), (":", Encoding::Selector),
+                               ("@", Encoding::Object(None)), ("@?", Encoding::Block), ("?", Encoding::Unknown), ("b13", Encoding::BitField(13))] {
+        assert_eq!(Encoding::parse(string), Ok(encoding.clone()));
+        assert_eq!(encoding.to_string(), string);
+    }
+}
+
+#[test] fn parse_compound() {
+    let range = Encoding::parse("{_NSRange=QQ}").unwrap();
+    assert_eq!(range, Encoding::Struct("_NSRange".to_owned(), Some(vec![Encoding::UnsignedLongLong, Encoding::UnsignedLongLong])));
+
+    let point = Encoding::Struct("CGPoint".to_owned(), Some(vec![Encoding::Double, Encoding::Double]));
+    let size = Encoding::Struct("CGSize".to_owned(), Some(vec![Encoding::Double, Encoding::Double]));
+    assert_eq!(Encoding::parse("{CGRect={CGPoint=dd}{CGSize=dd}}"), Ok(Encoding::Struct("CGRect".to_owned(), Some(vec![point.clone(), size]))));
+    assert_eq!(Encoding::parse("{CGPoint=\"x\"d\"y\"d}"), Ok(point.clone()));
+
+    assert_eq!(Encoding::parse("^{__CFString}"), Ok(Encoding::Pointer(Box::new(Encoding::Struct("__CFString".to_owned(), None)))));
+    assert_eq!(Encoding::parse("[16^v]"), Ok(Encoding::Array(16, Box::new(Encoding::Pointer(Box::new(Encoding::Void))))));
+    assert_eq!(Encoding::parse("(?=iq)"), Ok(Encoding::Union("?".to_owned(), Some(vec![Encoding::Int, Encoding::LongLong]))));
+    assert_eq!(Encoding::parse("@\"NSString\""), Ok(Encoding::Object(Some("NSString".to_owned()))));
+    let qualified = Encoding::parse("r^v").unwrap();
+    assert_eq!(qualified, Encoding::Qualified('r', Box::new(Encoding::Pointer(Box::new(Encoding::Void)))));
+    assert_eq!(qualified.unqualified(), &Encoding::Pointer(Box::new(Encoding::Void)));
+
+    for string in ["{CGRect={CGPoint=dd}{CGSize=dd}}", "^{__CFString}", "[16^v]", "(?=iq)", "@\"NSString\"", "r^v", "{empty=}", "^^[2{a=b3b5}]"] {
+        assert_eq!(Encoding::parse(string).unwrap().to_string(), string);
+    }
+}
+
+#[test] fn parse_errors() {
+    assert_eq!(Encoding::parse(""), Err(EncodingParseError::UnexpectedEnd));
+    assert_eq!(Encoding::parse("{_NSRange=QQ"), Err(EncodingParseError::UnexpectedEnd));
+    assert_eq!(Encoding::parse("[4i"), Err(EncodingParseError::UnexpectedEnd));
+    assert_eq!(Encoding::parse("[i]"), Err(EncodingParseError::InvalidNumber(1)));
+    assert_eq!(Encoding::parse("ii"), Err(EncodingParseError::TrailingCharacters(1)));
+    assert_eq!(Encoding::parse("{a=iz}"), Err(EncodingParseError::UnexpectedCharacter(4, 'z')));
+    assert_eq!(Encoding::parse("é"), Err(EncodingParseError::UnexpectedCharacter(0, 'é')));
+    assert_eq!(Encoding::parse("[2i)"), Err(EncodingParseError::UnexpectedCharacter(3, ')')));
+    assert_eq!(Encoding::parse("b99999999999"), Err(EncodingParseError::InvalidNumber(1)));
+
+    let pointers = "^".repeat(100_000);
+    assert_eq!(Encoding::parse(&pointers), Err(EncodingParseError::TooDeeplyNested(MAX_NESTING)));
+    let structs = "{a=".repeat(100_000);
+    assert_eq!(Encoding::parse(&structs), Err(EncodingParseError::TooDeeplyNested(MAX_NESTING * 3)));
+    assert_eq!(Encoding::parse_method_signature(&format!("v0{}", "[1".repeat(100_000))), Err(EncodingParseError::TooDeeplyNested(2 + MAX_NESTING * 2)));
+    let deepest = format!("{}i", "^".repeat(MAX_NESTING - 1));
+    assert_eq!(Encoding::parse(&deepest).unwrap().to_string(), deepest);
+}
+
+#[test] fn parse_method_signatures() {
+    let types = Encoding::parse_method_signature("v24@0:8@16").unwrap();
+    assert_eq!(types, [Encoding::Void, Encoding::Object(None), Encoding::Selector, Encoding::Object(None)]);
+    let types = Encoding::parse_method_signature("{_NSRange=QQ}16@0:8").unwrap();
+    assert_eq!(types.len(), 3);
+    let types = Encoding::parse_method_signature("@@:r^vQ").unwrap();
+    assert_eq!(types[3].unqualified(), &Encoding::Pointer(Box::new(Encoding::Void)));
+    assert_eq!(Encoding::parse_method_signature(""), Err(EncodingParseError::UnexpectedEnd));
+}
@@ -1,4 +1,45 @@
+use std::cmp::Ordering;
 use std::os::raw::{c_ulong, c_long};
 
 pub type NSUInteger = c_ulong;
-pub type NSInteger = c_long;
\ No newline at end of file
+pub type NSInteger = c_long;
+
+///`NSComparisonResult`
+#[repr(i64)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NSComparisonResult {
+    OrderedAscending = -1,
+    OrderedSame = 0,
+    OrderedDescending = 1,
+}
+impl NSComparisonResult {
+    ///Interprets a raw `NSComparisonResult`.  Foundation only promises the sign.
+    pub fn from_raw(raw: NSInteger) -> Self {
+        match raw.cmp(&0) {
+            Ordering::Less => NSComparisonResult::OrderedAscending,
+            Ordering::Equal => NSComparisonResult::OrderedSame,
+            Ordering::Greater => NSComparisonResult::OrderedDescending,
+        }
+    }
+    pub fn raw(self) -> NSInteger {
+        self as NSInteger
+    }
+}
+impl From<Ordering> for NSComparisonResult {
+    fn from(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Less => NSComparisonResult::OrderedAscending,
+            Ordering::Equal => NSComparisonResult::OrderedSame,
+            Ordering::Greater => NSComparisonResult::OrderedDescending,
+        }
+    }
+}
+impl From<NSComparisonResult> for Ordering {
+    fn from(result: NSComparisonResult) -> Self {
+        match result {
+            NSComparisonResult::OrderedAscending => Ordering::Less,
+            NSComparisonResult::OrderedSame => Ordering::Equal,
+            NSComparisonResult::OrderedDescending => Ordering::Greater,
+        }
+    }
+}